@@ -0,0 +1,132 @@
+//! Compares `advent_of_code::parse` against the hand-rolled digit loops it replaced.
+//! Run with `cargo bench --bench parse`.
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use advent_of_code::parse::{self, Spaces};
+use advent_of_code::template::rng::Rng;
+
+fn hand_rolled_u32<'a, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<u32> {
+    let mut number = 0;
+    let mut saw_number = false;
+    for c in input {
+        if !c.is_ascii_digit() {
+            break;
+        }
+
+        saw_number = true;
+        number *= 10;
+        number += u32::from(*c - b'0');
+    }
+
+    saw_number.then_some(number)
+}
+
+/// The line loop day 09 used, summing each line instead of extrapolating it.
+fn hand_rolled_i32_lines(input: &[u8]) -> i32 {
+    let mut input = input.iter();
+    let mut total = 0;
+    loop {
+        let mut line = vec![];
+        let mut number = 0;
+        let mut sign = 1;
+        for c in input.by_ref() {
+            if c.is_ascii_digit() {
+                number *= 10;
+                number += i32::from(*c - b'0');
+                continue;
+            }
+            if *c == b'-' {
+                sign = -1;
+                continue;
+            }
+            line.push(number * sign);
+            if *c == b'\n' {
+                break;
+            }
+            number = 0;
+            sign = 1;
+        }
+        if line.is_empty() {
+            break total;
+        }
+        total ^= line.into_iter().sum::<i32>();
+    }
+}
+
+fn parse_i32_lines(input: &[u8]) -> i32 {
+    let mut input = input.iter();
+    let mut total = 0;
+    loop {
+        let line: Vec<i32> = parse::line_numbers(&mut input).collect();
+        if line.is_empty() {
+            break total;
+        }
+        total ^= line.into_iter().sum::<i32>();
+    }
+}
+
+fn time<T>(name: &str, mut func: impl FnMut() -> T) {
+    let mut samples = vec![];
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_millis(500) {
+        let timer = Instant::now();
+        black_box(func());
+        samples.push(timer.elapsed());
+    }
+    println!("{name:<24} {:.1?}", samples[0]);
+}
+
+fn main() {
+    let mut rng = Rng::new(0);
+    let mut unsigned = String::new();
+    let mut signed = String::new();
+    for i in 0..100_000 {
+        let sep = if i % 10 == 9 { '\n' } else { ' ' };
+        write!(unsigned, "{}{sep}", rng.below(1 << 31)).unwrap();
+        write!(
+            signed,
+            "{}{sep}",
+            rng.range(0..2_000_000).cast_signed() - 1_000_000
+        )
+        .unwrap();
+    }
+
+    time("hand-rolled u32", || {
+        let mut input = black_box(unsigned.as_bytes()).iter();
+        let mut total = 0;
+        while let Some(n) = hand_rolled_u32(&mut input) {
+            total += u64::from(n);
+        }
+        total
+    });
+    time("parse::read u32", || {
+        let mut input = black_box(unsigned.as_bytes()).iter();
+        let mut total = 0;
+        while let Some(n) = parse::read::<u32, _>(&mut input) {
+            total += u64::from(n);
+        }
+        total
+    });
+    time("parse::read_with u32", || {
+        let mut input = black_box(unsigned.as_bytes()).iter();
+        let mut total = 0;
+        while let Some(n) = parse::read_with::<u32, _>(&mut input, Spaces::Leading) {
+            total += u64::from(n);
+        }
+        total
+    });
+    time("parse::numbers u32", || {
+        parse::numbers::<u32>(black_box(unsigned.as_bytes()))
+            .map(u64::from)
+            .sum::<u64>()
+    });
+
+    time("hand-rolled i32 lines", || {
+        hand_rolled_i32_lines(black_box(signed.as_bytes()))
+    });
+    time("parse::line_numbers i32", || {
+        parse_i32_lines(black_box(signed.as_bytes()))
+    });
+}
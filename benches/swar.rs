@@ -0,0 +1,118 @@
+//! Compares `advent_of_code::swar` against the byte-by-byte loops days 01, 03 and 04 use.
+//! Run with `cargo bench --bench swar`.
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use advent_of_code::parse;
+use advent_of_code::swar;
+use advent_of_code::template::rng::Rng;
+
+fn time<T>(name: &str, mut func: impl FnMut() -> T) {
+    let mut samples = vec![];
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_millis(500) {
+        let timer = Instant::now();
+        black_box(func());
+        samples.push(timer.elapsed());
+    }
+    samples.sort_unstable();
+    println!("{name:<28} {:.1?}", samples[samples.len() / 2]);
+}
+
+/// Scratchcards like day 04's, with long lines.
+fn cards(rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for card in 1..=20_000 {
+        write!(input, "Card {card:>5}:").unwrap();
+        for i in 0..35 {
+            let sep = if i == 10 { " |" } else { "" };
+            write!(input, "{sep} {:>2}", rng.range(1..100)).unwrap();
+        }
+        input.push('\n');
+    }
+    input
+}
+
+/// An engine schematic like day 03's: numbers and symbols between dots.
+fn schematic(rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..1_000 {
+        for _ in 0..140 {
+            match rng.below(10) {
+                0 => write!(input, "{}", rng.range(1..1000)).unwrap(),
+                1 => input.push('*'),
+                _ => input.push('.'),
+            }
+        }
+        input.push('\n');
+    }
+    input
+}
+
+fn main() {
+    let mut rng = Rng::new(0);
+    let cards = cards(&mut rng);
+    let schematic = schematic(&mut rng);
+
+    time("position '\\n'", || {
+        let mut rest = black_box(cards.as_bytes());
+        let mut lines = 0;
+        while let Some(end) = rest.iter().position(|&c| c == b'\n') {
+            rest = &rest[end + 1..];
+            lines += 1;
+        }
+        lines
+    });
+    time("str::lines", || black_box(cards.as_str()).lines().count());
+    time("swar::lines", || {
+        swar::lines(black_box(cards.as_bytes())).count()
+    });
+
+    time("position ':' per line", || {
+        black_box(cards.as_str())
+            .lines()
+            .filter_map(|line| line.bytes().position(|c| c == b':'))
+            .sum::<usize>()
+    });
+    time("swar::find_byte ':' per line", || {
+        swar::lines(black_box(cards.as_bytes()))
+            .filter_map(|line| swar::find_byte(b':', line))
+            .sum::<usize>()
+    });
+
+    time("byte loop digit runs", || {
+        let mut runs = 0;
+        let mut in_run = false;
+        for &c in black_box(schematic.as_bytes()) {
+            let digit = c.is_ascii_digit();
+            runs += usize::from(digit && !in_run);
+            in_run = digit;
+        }
+        runs
+    });
+    time("swar::digit_runs", || {
+        swar::digit_runs(black_box(schematic.as_bytes())).count()
+    });
+
+    time("parse::numbers cards", || {
+        black_box(cards.as_str())
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(_, numbers)| parse::numbers::<u32>(numbers.as_bytes()).sum::<u32>())
+            .sum::<u32>()
+    });
+    time("swar::two_digit_fields cards", || {
+        swar::lines(black_box(cards.as_bytes()))
+            .filter_map(|line| {
+                let numbers = &line[swar::find_byte(b':', line)? + 1..];
+                let bar = swar::find_byte(b'|', numbers)?;
+                let fields = |numbers| swar::two_digit_fields(numbers, 3).map(u32::from);
+                Some(
+                    fields(&numbers[1..bar]).sum::<u32>()
+                        + fields(&numbers[bar + 2..]).sum::<u32>(),
+                )
+            })
+            .sum::<u32>()
+    });
+}
@@ -1,14 +1,25 @@
-advent_of_code::solution!(1);
-
-// macro_rules! regex {
-//     ($re:literal $(,)?) => {{
-//         static RE: once_cell::sync::OnceCell<regex::Regex> = once_cell::sync::OnceCell::new();
-//         RE.get_or_init(|| regex::Regex::new($re).unwrap())
-//     }};
-// }
+use std::io::{self, BufRead};
+
+use advent_of_code::matcher::Matcher;
+use advent_of_code::stream::LineChunks;
+use advent_of_code::swar;
+
+advent_of_code::solution!(
+    1,
+    part_two: [part_two_regex],
+    stream_one: stream_part_one,
+    stream_two: stream_part_two,
+);
+
+macro_rules! regex {
+    ($re:literal $(,)?) => {{
+        static RE: once_cell::sync::OnceCell<regex::Regex> = once_cell::sync::OnceCell::new();
+        RE.get_or_init(|| regex::Regex::new($re).unwrap())
+    }};
+}
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let input = input.as_bytes();
+/// Sums the numbers made of the first and last digit of each line.
+fn calibration_sum(input: &[u8]) -> u32 {
     let mut total = 0;
     let mut first = b'0';
     let mut last = b'0';
@@ -36,144 +47,95 @@ pub fn part_one(input: &str) -> Option<u32> {
     if first != b'0' || last != b'0' {
         total += u32::from((first - b'0') * 10 + (last - b'0'));
     }
-    Some(total)
+    total
 }
 
-// fn parse(input: &str) -> Option<u32> {
-//     match input {
-//         "0" | "zero" => Some(0),
-//         "1" | "one" => Some(1),
-//         "2" | "two" => Some(2),
-//         "3" | "three" => Some(3),
-//         "4" | "four" => Some(4),
-//         "5" | "five" => Some(5),
-//         "6" | "six" => Some(6),
-//         "7" | "seven" => Some(7),
-//         "8" | "eight" => Some(8),
-//         "9" | "nine" => Some(9),
-//         _ => None,
-//     }
-// }
-
-const WORDS: [(&[u8], u8); 9] = [
-    (b"one", b'1'),
-    (b"two", b'2'),
-    (b"three", b'3'),
-    (b"four", b'4'),
-    (b"five", b'5'),
-    (b"six", b'6'),
-    (b"seven", b'7'),
-    (b"eight", b'8'),
-    (b"nine", b'9'),
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(calibration_sum(input.as_bytes()))
+}
+
+pub fn stream_part_one(input: &mut dyn BufRead) -> io::Result<Option<u32>> {
+    LineChunks::new(input)
+        .fold(0, |total, chunk| total + calibration_sum(chunk))
+        .map(Some)
+}
+
+fn parse(input: &str) -> Option<u32> {
+    match input {
+        "0" | "zero" => Some(0),
+        "1" | "one" => Some(1),
+        "2" | "two" => Some(2),
+        "3" | "three" => Some(3),
+        "4" | "four" => Some(4),
+        "5" | "five" => Some(5),
+        "6" | "six" => Some(6),
+        "7" | "seven" => Some(7),
+        "8" | "eight" => Some(8),
+        "9" | "nine" => Some(9),
+        _ => None,
+    }
+}
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
 ];
 
-// Using the FILTER actually slows down the code for some reason
-// which letters appear in WORDS, found with:
-// let mut letters: Vec<u8> = WORDS.iter().map(|(w,_)| w.iter().cloned()).flatten().collect();
-// letters.sort_unstable();
-// letters.dedup();
-// let filter = letters.iter().fold(0u32, |acc, letter| {
-//     acc | 1 << (letter - b'a')
-// });
-// println!("{}", std::str::from_utf8(&letters[..]).unwrap());
-// println!("{filter:b}");
-// const FILTER: u32 = 0b111111100110000111110000;
-
-#[allow(clippy::bool_to_int_with_if)]
+/// Matches digits and spelled out digits, overlapping ones too ("eightwo").
+fn digit_matcher() -> &'static Matcher<u32> {
+    static MATCHER: once_cell::sync::OnceCell<Matcher<u32>> = once_cell::sync::OnceCell::new();
+    MATCHER.get_or_init(|| {
+        let words = WORDS.iter().map(|word| word.as_bytes().to_vec());
+        let digits = (b'1'..=b'9').map(|digit| vec![digit]);
+        Matcher::new(words.zip(1..).chain(digits.zip(1..)))
+    })
+}
+
+/// Like [`calibration_sum`], with spelled out digits too.
+fn spelled_calibration_sum(input: &[u8]) -> u32 {
+    let matcher = digit_matcher();
+    swar::lines(input)
+        .filter_map(|line| {
+            let first = matcher.first(line)?;
+            let last = matcher.last(line)?;
+            Some(first.value * 10 + last.value)
+        })
+        .sum()
+}
+
 pub fn part_two(input: &str) -> Option<u32> {
-    let input = input.as_bytes();
-    let mut total = 0;
-    let mut first = b'0';
-    let mut last = b'0';
-    let mut reset_words = false;
-    let mut word_positions = [0; 9];
-    for b in input {
-        let mut b = *b;
-        if b < b'1' {
-            if b == b'\n' {
-                total += u32::from((first - b'0') * 10 + (last - b'0'));
-                first = b'0';
-                last = b'0';
-            }
-            reset_words = true;
-            continue;
-        }
+    Some(spelled_calibration_sum(input.as_bytes()))
+}
 
-        if b > b'9' {
-            if !(b'e'..=b'x').contains(&b) {
-                // || (FILTER >> (b - b'a')) & 1 == 0 {
-                reset_words = true;
-                continue;
-            }
-            if reset_words {
-                word_positions = [0; 9];
-                reset_words = false;
-            }
-            let mut matched = false;
-            let mut the_match = b'0';
-            // this wouldn't work if the words had a second occurance of
-            // their initial letter, "nine" would be a problem, but the
-            // string that would trip this up "ninine" doesn't appear in the
-            // input.
-            for (pos, word_item) in word_positions.iter_mut().zip(WORDS) {
-                let word = word_item.0;
-                *pos = if b == word[*pos] {
-                    let new = *pos + 1;
-                    if new == word.len() {
-                        matched = true;
-                        the_match = word_item.1;
-                        0
-                    } else {
-                        new
+pub fn stream_part_two(input: &mut dyn BufRead) -> io::Result<Option<u32>> {
+    LineChunks::new(input)
+        .fold(0, |total, chunk| total + spelled_calibration_sum(chunk))
+        .map(Some)
+}
+
+// this version is basically as fast, but much more straight-forward.
+pub fn part_two_regex(input: &str) -> Option<u32> {
+    let re = regex!(r"[0-9]|one|two|three|four|five|six|seven|eight|nine");
+    let rere = regex!(r"\A(?:[0-9]|one|two|three|four|five|six|seven|eight|nine)");
+    Some(input.split('\n').fold(0, |total, s| {
+        let mut found = false;
+        let tens = re.find(s).map_or(0, |num| {
+            found = true;
+            parse(num.as_str()).unwrap_or(0)
+        });
+        let ones = if found {
+            'ones: {
+                for i in (0..s.len()).rev() {
+                    if let Some(num) = rere.find(&s[i..]) {
+                        break 'ones parse(num.as_str()).unwrap_or(0);
                     }
-                } else if b == word[0] {
-                    1
-                } else {
-                    0
-                };
+                }
+                0
             }
-            if matched {
-                b = the_match;
-            } else {
-                continue;
-            }
-        }
-
-        if first == b'0' {
-            first = b;
-        }
-
-        last = b;
-    }
-    if first != b'0' || last != b'0' {
-        total += u32::from((first - b'0') * 10 + (last - b'0'));
-    }
-    Some(total)
-
-    // this version is basically as fast, but much more straight-forward:
-    //
-    // let re = regex!(r"[0-9]|one|two|three|four|five|six|seven|eight|nine");
-    // let rere = regex!(r"\A(?:[0-9]|one|two|three|four|five|six|seven|eight|nine)");
-    // Some(input.split('\n').fold(0, |total, s| {
-    //     let mut found = false;
-    //     let tens = re.find(s).map_or(0, |num| {
-    //         found = true;
-    //         parse(num.as_str()).unwrap_or(0)
-    //     });
-    //     let ones = if found {
-    //         'ones: {
-    //             for i in (0..s.len()).rev() {
-    //                 if let Some(num) = rere.find(&s[i..]) {
-    //                     break 'ones parse(num.as_str()).unwrap_or(0);
-    //                 }
-    //             }
-    //             0
-    //         }
-    //     } else {
-    //         0
-    //     };
-    //     total + tens * 10 + ones
-    // }))
+        } else {
+            0
+        };
+        total + tens * 10 + ones
+    }))
 }
 
 #[cfg(test)]
@@ -195,4 +157,21 @@ mod tests {
         ));
         assert_eq!(result, Some(281));
     }
+
+    #[test]
+    fn test_part_two_overlaps() {
+        let input = "ninine\neightwo\nxoneightx\nnothing\n";
+        assert_eq!(part_two(input), Some(99 + 82 + 18));
+        assert_eq!(part_two_regex(input), part_two(input));
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        assert_eq!(stream_part_two(&mut input.as_bytes()).unwrap(), Some(281));
+        assert_eq!(
+            stream_part_one(&mut &b"a1b\n2c3d"[..]).unwrap(),
+            Some(11 + 23)
+        );
+    }
 }
@@ -1,99 +1,48 @@
-advent_of_code::solution!(4);
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut input = input.as_bytes().iter();
-    let mut total = 0;
-    loop {
-        let mut saw_colon = false;
-        for &c in input.by_ref() {
-            if c == b':' {
-                saw_colon = true;
-                break;
-            }
-        }
-
-        if !saw_colon {
-            break Some(total);
-        }
+use advent_of_code::bits::BitSet;
+use advent_of_code::swar;
 
-        let mut winners = vec![];
-        loop {
-            input.next();
-            match (input.next(), input.next()) {
-                (Some(b'|'), _) => break,
-                (Some(a), Some(b)) => {
-                    winners.push(u32::from_ne_bytes([0, 0, *a, *b]));
-                }
-                _ => break,
-            }
-        }
+advent_of_code::solution!(4);
 
-        let mut matches = 0;
-        loop {
-            let (Some(a), Some(b)) = (input.next(), input.next()) else {
-                break;
-            };
-            matches += u32::from(winners.contains(&u32::from_ne_bytes([0, 0, *a, *b])));
-            if input.next() == Some(&b'\n') {
-                break;
-            }
-        }
+/// Returns how many of the numbers on each card are winning numbers.
+fn card_matches(input: &str) -> impl Iterator<Item = usize> + '_ {
+    swar::lines(input.as_bytes()).filter_map(|line| {
+        let numbers = &line[swar::find_byte(b':', line)? + 1..];
+        let bar = swar::find_byte(b'|', numbers)?;
+        // the numbers are below 100 and right-aligned in fields of two, after a space
+        let numbers_from = |start| {
+            let fields = swar::two_digit_fields(numbers.get(start..)?, 3);
+            Some(fields.map(usize::from))
+        };
+        let winners: BitSet<2> = numbers_from(1)?.collect();
+        Some(
+            numbers_from(bar + 2)?
+                .filter(|&number| winners.contains(number))
+                .count(),
+        )
+    })
+}
 
-        if matches > 0 {
-            total += 2u32.pow(matches - 1);
-        }
-    }
+pub fn part_one(input: &str) -> Option<u32> {
+    card_matches(input)
+        .filter(|&matches| matches > 0)
+        .map(|matches| 1u32.checked_shl(u32::try_from(matches - 1).ok()?))
+        .sum()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut input = input.as_bytes().iter();
-    let mut total = 0;
+    let mut total = 0u32;
     let mut card_counts = std::collections::VecDeque::new();
-    loop {
-        let mut saw_colon = false;
-        for &c in input.by_ref() {
-            if c == b':' {
-                saw_colon = true;
-                break;
-            }
-        }
-
-        if !saw_colon {
-            break Some(total);
-        }
-
+    for matches in card_matches(input) {
         let copies = card_counts.pop_front().unwrap_or(0) + 1;
-        total += copies;
-
-        let mut winners = vec![];
-        loop {
-            input.next();
-            match (input.next(), input.next()) {
-                (Some(b'|'), _) => break,
-                (Some(a), Some(b)) => {
-                    winners.push(u32::from_ne_bytes([0, 0, *a, *b]));
-                }
-                _ => break,
-            }
-        }
-
-        let mut matches = 0;
-        loop {
-            let (Some(a), Some(b)) = (input.next(), input.next()) else {
-                break;
-            };
-            matches += u32::from(winners.contains(&u32::from_ne_bytes([0, 0, *a, *b])));
-            if input.next() == Some(&b'\n') {
-                break;
-            }
-        }
+        total = total.checked_add(copies)?;
 
-        let mut adds = std::iter::repeat(copies).take(matches as usize);
+        let mut adds = std::iter::repeat_n(copies, matches);
         for (c, _) in card_counts.iter_mut().zip(adds.by_ref()) {
             *c += copies;
         }
         card_counts.extend(adds);
     }
+    Some(total)
 }
 
 #[cfg(test)]
@@ -1,131 +1,95 @@
-use range_set::range_set;
+use advent_of_code::interval::{IntervalSet, RangeMap};
+use advent_of_code::parse;
+use advent_of_code::template::rng::Rng;
 
-advent_of_code::solution!(5);
+advent_of_code::solution!(5, generator: generate);
 
-fn read_u32<'a, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<u32> {
-    let mut number = 0;
-    let mut saw_number = false;
-    for c in input {
-        if !c.is_ascii_digit() {
-            break;
-        }
-
-        saw_number = true;
-        number *= 10;
-        number += u32::from(*c - b'0');
-    }
-
-    saw_number.then_some(number)
-}
-
-// keep list of slices, partition them based on new ranges
-// once processed, add them to a (sorted?) list to combine later
-
-// dest_start source_start range_length
-pub fn part_one(input: &str) -> Option<u32> {
+/// Returns the seed numbers, and the map from seeds to locations that all of the almanac's maps
+/// combine into.
+fn read_almanac(input: &str) -> Option<(Vec<u32>, RangeMap<u32>)> {
     let mut input = input.as_bytes().iter();
 
-    let mut ids = vec![];
-
-    input.find(|c| **c == b':');
+    input.find(|c| **c == b':')?;
     input.next();
-    while let Some(seed) = read_u32(&mut input) {
-        ids.push(Some(seed));
+    let mut seeds = vec![];
+    while let Some(seed) = parse::read(&mut input) {
+        seeds.push(seed);
     }
 
-    // while input.find(|c| **c == b':').is_some() {
+    let mut pipeline = RangeMap::new();
     while input.any(|&c| c == b':') {
         input.next();
-        ids.sort();
-        let mut first = 0;
-        let mut last = ids.len() - 1;
-        let mut next_ids = ids.clone();
-        while let Some(dest_start) = read_u32(&mut input) {
-            let source_start = read_u32(&mut input).unwrap();
-            let range_length = read_u32(&mut input).unwrap();
-            let source_end = source_start + (range_length - 1);
-            if ids[first] > Some(source_end) || ids[last] < Some(source_start) {
-                continue;
-            }
-            let range = first..=last;
-            for i in range {
-                let mut o = ids[i];
-                let Some(num) = o else {
-                    first += usize::from(i == first);
-                    continue;
-                };
-                if num < source_start {
-                    continue;
-                }
-                if num > source_end {
-                    break;
-                }
-                next_ids[i] = o.take().map(|num| num - source_start + dest_start);
-                first += usize::from(i == first);
-            }
-            if first > last {
-                break;
-            }
-            while ids[last].is_none() {
-                last -= 1;
-            }
+        let mut map = RangeMap::new();
+        // dest_start source_start range_length
+        while let Some(dest_start) = parse::read(&mut input) {
+            let source_start: u32 = parse::read(&mut input)?;
+            let range_length: u32 = parse::read(&mut input)?;
+            let source_end = source_start.checked_add(range_length.checked_sub(1)?)?;
+            map.insert(source_start..=source_end, dest_start)
+                .then_some(())?;
         }
-        ids = next_ids;
+        pipeline = pipeline.then(&map);
     }
-    ids.into_iter().flatten().min()
+    Some((seeds, pipeline))
 }
 
-fn read_u64<'a, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<u64> {
-    let mut number = 0;
-    let mut saw_number = false;
-    for c in input {
-        if !c.is_ascii_digit() {
-            break;
-        }
-
-        saw_number = true;
-        number *= 10;
-        number += u64::from(*c - b'0');
-    }
-
-    saw_number.then_some(number)
+pub fn part_one(input: &str) -> Option<u32> {
+    let (seeds, pipeline) = read_almanac(input)?;
+    seeds.into_iter().map(|seed| pipeline.get(seed)).min()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut input = input.as_bytes().iter();
-
-    let mut id_ranges = range_set![];
-
-    input.find(|c| **c == b':');
-    input.next();
-    // need read_u64 here because range_set doesn't play nice with ranges
-    // containing T::MAX https://github.com/spearman/range-set/issues/14
-    while let (Some(id_start), Some(length)) = (read_u64(&mut input), read_u64(&mut input)) {
-        id_ranges.insert_range(id_start..=(id_start - 1 + length));
+    let (seeds, pipeline) = read_almanac(input)?;
+    let pairs = seeds.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
     }
+    let seeds: IntervalSet<_> = pairs
+        .map(|pair| Some(pair[0]..=pair[0].checked_add(pair[1].checked_sub(1)?)?))
+        .collect::<Option<_>>()?;
+    pipeline.apply(&seeds).min()
+}
 
-    while input.any(|&c| c == b':') {
-        input.next();
-        let mut next_id_ranges = range_set![];
-        while let Some(dest_start) = read_u64(&mut input) {
-            let source_start = read_u64(&mut input).unwrap();
-            let range_length = read_u64(&mut input).unwrap();
-            let source_end = source_start + (range_length - 1);
-            if let Some(rs) = id_ranges.remove_range(source_start..=source_end) {
-                for r in rs.into_smallvec() {
-                    next_id_ranges.insert_range(
-                        (r.start() - source_start + dest_start)
-                            ..=(r.end() - source_start + dest_start),
-                    );
-                }
-            }
-        }
-        for r in id_ranges.into_smallvec() {
-            next_id_ranges.insert_range(r);
+const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+// keep every number well below `u32::MAX`.
+const MAX_ID: u64 = 1 << 30;
+
+/// Generates an almanac with `size` seed ranges and `size` ranges in each map.
+pub fn generate(size: usize, seed: u64) -> String {
+    use std::fmt::Write;
+
+    let mut rng = Rng::new(seed);
+    let mut input = String::from("seeds:");
+    for _ in 0..size.max(1) {
+        let start = rng.below(MAX_ID / 2);
+        let length = rng.range(1..MAX_ID / 2 / size.max(1) as u64 + 2);
+        write!(input, " {start} {length}").unwrap();
+    }
+    input.push('\n');
+
+    for name in MAP_NAMES {
+        write!(input, "\n{name} map:\n").unwrap();
+        // non-overlapping source ranges between sorted cut points.
+        let mut cuts: Vec<_> = (0..=size).map(|_| rng.below(MAX_ID)).collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+        let mut ranges: Vec<_> = cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+        rng.shuffle(&mut ranges);
+        for (source_start, range_length) in ranges {
+            let dest_start = rng.below(MAX_ID - range_length);
+            writeln!(input, "{dest_start} {source_start} {range_length}").unwrap();
         }
-        id_ranges = next_id_ranges;
     }
-    id_ranges.min().and_then(|x| u32::try_from(x).ok())
+    input
 }
 
 #[cfg(test)]
@@ -143,4 +107,26 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(46));
     }
+
+    #[test]
+    fn test_ranges_up_to_max() {
+        let input = "seeds: 4294967290 6\n\nseed-to-soil map:\n0 4294967295 1\n";
+        assert_eq!(part_one(input), Some(6));
+        assert_eq!(part_two(input), Some(0));
+    }
+
+    #[test]
+    fn test_odd_seeds() {
+        let input = "seeds: 79 14 55\n\nseed-to-soil map:\n50 98 2\n";
+        assert_eq!(part_one(input), Some(14));
+        assert_eq!(part_two(input), None);
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(50, 1);
+        assert_eq!(input, generate(50, 1));
+        assert!(part_one(&input).is_some());
+        assert!(part_two(&input).is_some());
+    }
 }
@@ -1,96 +1,62 @@
-advent_of_code::solution!(6);
+use advent_of_code::math;
+use advent_of_code::parse::{self, Spaces};
 
-fn read_f64<'a, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<f64> {
-    let mut number = 0.0;
-    let mut saw_number = false;
-    let mut skipping = true;
-    for c in input {
-        if skipping && *c == b' ' {
-            continue;
-        }
-        skipping = false;
-        if !c.is_ascii_digit() {
-            break;
-        }
+advent_of_code::solution!(6);
 
-        saw_number = true;
-        number *= 10.0;
-        number += f64::from(*c - b'0');
+/// Returns how many ways there are to beat `dist` in a race lasting `time`.
+fn ways_to_win(time: u64, dist: u64) -> u64 {
+    // holding the button for x travels x(time - x), which beats dist between the roots of
+    // x^2 - (time)x + dist = 0: x = (time +/- sqrt(time^2 - 4 * dist)) / 2
+    let (time, dist) = (u128::from(time), u128::from(dist));
+    let wins = |x: u128| x * (time - x) > dist;
+    let Some(discriminant) = (time * time).checked_sub(4 * dist) else {
+        return 0;
+    };
+    // the integer root is within one of the real one, so nudge it to the first win
+    let mut low = (time - math::isqrt(discriminant).unwrap()) / 2;
+    while low > 0 && wins(low - 1) {
+        low -= 1;
     }
-
-    saw_number.then_some(number)
+    while low <= time / 2 && !wins(low) {
+        low += 1;
+    }
+    if low > time / 2 {
+        return 0;
+    }
+    // the race is symmetric, so the last win is as far from the end as the first from the start
+    u64::try_from(time - 2 * low + 1).unwrap()
 }
 
-#[allow(clippy::cast_sign_loss)]
-pub fn part_one(input: &str) -> Option<u32> {
-    // x(time - x) = best
-    // -x^2 + (time)x - best = 0
-    // x^2 - (time)x + best = 0
-    // x^2 -7x + 9 = 0
-    // x = (-time +/- sqrt(time^2 - 4 * distance)) / 2
-
+pub fn part_one(input: &str) -> Option<u64> {
     let mut input = input.as_bytes().iter();
     input.find(|c| **c == b':');
     let mut times = vec![];
-    while let Some(time) = read_f64(&mut input) {
+    while let Some(time) = parse::read_with::<u64, _>(&mut input, Spaces::Leading) {
         times.push(time);
     }
 
     input.find(|c| **c == b':');
     let mut distances = vec![];
-    while let Some(dist) = read_f64(&mut input) {
+    while let Some(dist) = parse::read_with::<u64, _>(&mut input, Spaces::Leading) {
         distances.push(dist);
     }
-    Some(
-        times
-            .into_iter()
-            .zip(distances)
-            .map(|(time, dist)| {
-                let add = 4f64.mul_add(-dist, time.powi(2)).sqrt();
-                let high = (((time + add) / 2.0) - 1.0).ceil() as u32;
-                let low = (((time - add) / 2.0) + 1.0).floor() as u32;
-                high - low + 1
-            })
-            .product(),
-    )
+    times
+        .into_iter()
+        .zip(distances)
+        .try_fold(1u64, |product, (time, dist)| {
+            product.checked_mul(ways_to_win(time, dist))
+        })
 }
 
-fn read_f64_ignore_spaces<'a, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<f64> {
-    let mut number = 0.0;
-    let mut saw_number = false;
-    for c in input {
-        if *c == b' ' {
-            continue;
-        }
-        if !c.is_ascii_digit() {
-            break;
-        }
-
-        saw_number = true;
-        number *= 10.0;
-        number += f64::from(*c - b'0');
-    }
-
-    saw_number.then_some(number)
-}
-
-#[allow(clippy::cast_sign_loss)]
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Option<u64> {
     let mut input = input.as_bytes().iter();
     input.find(|c| **c == b':');
-    let Some(time) = read_f64_ignore_spaces(&mut input) else {
-        return None;
-    };
+    let time = parse::read_with::<u64, _>(&mut input, Spaces::Ignore)?;
 
     input.find(|c| **c == b':');
-    let Some(dist) = read_f64_ignore_spaces(&mut input) else {
-        return None;
-    };
+    let dist = parse::read_with::<u64, _>(&mut input, Spaces::Ignore)?;
 
-    let add = 4f64.mul_add(-dist, time.powi(2)).sqrt();
-    let high = (((time + add) / 2.0) - 1.0).ceil() as u32;
-    let low = (((time - add) / 2.0) + 1.0).floor() as u32;
-    Some(high - low + 1)
+    Some(ways_to_win(time, dist))
 }
 
 #[cfg(test)]
@@ -108,4 +74,23 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(71503));
     }
+
+    #[test]
+    fn test_ways_to_win() {
+        assert_eq!(ways_to_win(7, 9), 4);
+        assert_eq!(ways_to_win(30, 200), 9);
+        // exactly reaching the distance isn't a win
+        assert_eq!(ways_to_win(4, 4), 0);
+        assert_eq!(ways_to_win(4, 3), 1);
+        assert_eq!(ways_to_win(4, 100), 0);
+        assert_eq!(ways_to_win(0, 0), 0);
+        // time^2 needs 80 bits, far more than f64 keeps
+        let time = 1 << 40;
+        let low = 1 << 12;
+        assert_eq!(
+            ways_to_win(time, low * (time - low) - 1),
+            time - 2 * low + 1
+        );
+        assert_eq!(ways_to_win(time, low * (time - low)), time - 2 * low - 1);
+    }
 }
@@ -1,3 +1,4 @@
+use advent_of_code::parse::{self, Spaces};
 use itertools::Itertools;
 use std::cmp::Ordering;
 use std::marker::PhantomData;
@@ -186,7 +187,7 @@ impl<T: TryFrom<LabelCounts<L>, Error = &'static str>, L: Label> Hand<T, L> {
             })
             .into();
         input.next();
-        let bid = read_u32(input).ok_or("failed to read bid")?;
+        let bid = parse::read_with(input, Spaces::Leading).ok_or("failed to read bid")?;
         Ok(Self {
             strength: (label_counts.try_into()?, labels),
             bid,
@@ -206,27 +207,6 @@ impl<T: Ord, L: Ord> Ord for Hand<T, L> {
     }
 }
 
-fn read_u32<'a, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<u32> {
-    let mut number = 0;
-    let mut saw_number = false;
-    let mut skipping = true;
-    for c in input {
-        if skipping && *c == b' ' {
-            continue;
-        }
-        skipping = false;
-        if !c.is_ascii_digit() {
-            break;
-        }
-
-        saw_number = true;
-        number *= 10;
-        number += u32::from(*c - b'0');
-    }
-
-    saw_number.then_some(number)
-}
-
 pub fn part_one(input: &str) -> Option<u32> {
     let mut input = input.as_bytes().iter();
     Some(
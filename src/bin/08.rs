@@ -1,317 +1,182 @@
-use std::collections::HashMap;
+use advent_of_code::collections::Interner;
+use advent_of_code::cycle::{self, Cycle};
+use advent_of_code::math;
+use advent_of_code::parallel;
+use advent_of_code::scan;
+use advent_of_code::scan::Scanner;
 
 advent_of_code::solution!(8);
 
-fn read_ident<'a, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<u32> {
-    match (input.next(), input.next(), input.next()) {
-        (Some(a), Some(b), Some(c)) => Some(u32::from_ne_bytes([0, *a, *b, *c])),
-        _ => None,
-    }
+/// The instructions, and each node's left and right neighbors, indexed by interned name.
+struct Network<'a> {
+    instructions: &'a [u8],
+    names: Interner,
+    neighbors: Vec<Option<[usize; 2]>>,
 }
 
-fn read_node<'a, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<(u32, (u32, u32))> {
-    let key = read_ident(input)?;
-    input.find(|c| **c == b'(');
-    let left = read_ident(input)?;
-    input.find(|c| **c == b' ');
-    let right = read_ident(input)?;
-    input.find(|c| **c == b'\n');
-    Some((key, (left, right)))
+impl Network<'_> {
+    fn step(&self, position: usize, turn: u8) -> Option<usize> {
+        match (turn, self.neighbors[position]) {
+            (b'L', Some([left, _])) => Some(left),
+            (b'R', Some([_, right])) => Some(right),
+            _ => None,
+        }
+    }
 }
 
-const START: u32 = u32::from_ne_bytes([0, b'A', b'A', b'A']);
-const END: u32 = u32::from_ne_bytes([0, b'Z', b'Z', b'Z']);
+fn read_node(scanner: &mut Scanner) -> Option<[[u8; 3]; 3]> {
+    let node = scan!(scanner, {[u8; 3]} " = (" {[u8; 3]} ", " {[u8; 3]} ")").ok()?;
+    scanner.eat(b"\n");
+    Some(node.into())
+}
 
-pub fn part_one(input: &str) -> Option<u32> {
+fn read_network(input: &str) -> Option<Network<'_>> {
     let input = input.as_bytes();
     let mut iter = input.splitn(2, |&c| c == b'\n');
     let instructions = iter.next()?;
-    let mut input = iter.next()?.iter();
-    input.next();
-    let map: HashMap<_, _> = std::iter::from_fn(|| read_node(&mut input)).collect();
-    // println!("{instructions:?}");
-    // println!("{map:?}");
+    let mut scanner = Scanner::new(iter.next()?);
+    scanner.eat(b"\n");
+    let mut names = Interner::new();
+    let mut neighbors = vec![];
+    while let Some(node) = read_node(&mut scanner) {
+        let [key, left, right] = node.map(|name| names.intern(&name));
+        neighbors.resize(names.len(), None);
+        neighbors[key] = Some([left, right]);
+    }
+    Some(Network {
+        instructions,
+        names,
+        neighbors,
+    })
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let network = read_network(input)?;
+    let end = network.names.get(b"ZZZ")?;
 
     let mut steps = 0;
-    let mut position = START;
-    for turn in instructions.iter().cycle() {
-        position = match (turn, map.get(&position)) {
-            (b'L', Some((ident, _))) | (b'R', Some((_, ident))) => *ident,
-            _ => None?,
-        };
+    let mut position = network.names.get(b"AAA")?;
+    for &turn in network.instructions.iter().cycle() {
+        position = network.step(position, turn)?;
         steps += 1;
-        if position == END {
+        if position == end {
             break;
         }
     }
     Some(steps)
 }
 
-// adapted from https://en.wikipedia.org/wiki/Binary_GCD_algorithm
-// I don't think I need this?
-// LCM(a, b) = a * (b / GCD(a, b))
-pub fn gcd(mut u: u32, mut v: u32) -> u32 {
-    match (u, v) {
-        (0, x) | (x, 0) => return x,
-        _ => {}
-    }
-
-    if u == v {
-        return u;
-    }
-
-    let u_trailing = u.trailing_zeros();
-    let v_trailing = v.trailing_zeros();
-    let gcd_exponent_on_two = u_trailing.min(v_trailing);
-
-    u >>= u_trailing;
-    v >>= v_trailing;
-
-    while u != v {
-        if u < v {
-            (u, v) = (v, u);
-        }
-        u -= v;
-        u >>= u.trailing_zeros();
-    }
-
-    u << gcd_exponent_on_two
-}
-
-const COUNTER_PRIMES: [u8; 8] = [5, 7, 11, 13, 17, 19, 23, 29];
-const CHECK_STARTING_INDEX: usize = 2 + COUNTER_PRIMES.len();
-
-// adapted from https://zsmith.co/primes.php
+/// The times a ghost is on an end node: once each in `once`, and then forever every `period`
+/// steps from each time in `repeating`.
 #[derive(Debug)]
-struct PrimeIterator {
-    primes: Vec<u32>,
-    index: usize,
-    counters: [u8; 8],
-    check_to_index: usize,
-    check_safe_until: u32,
-    skip_four: bool,
+struct EndTimes {
+    once: Vec<u64>,
+    repeating: Vec<u64>,
+    period: u64,
 }
 
-impl PrimeIterator {
-    fn new() -> Self {
+impl EndTimes {
+    /// Sorts the times a ghost is on an end node during the first `cycle.start + cycle.length`
+    /// steps into the ones that happen once and the ones that repeat with the cycle.
+    fn new(ends: impl IntoIterator<Item = u64>, cycle: Cycle) -> Self {
+        let (once, repeating) = ends
+            .into_iter()
+            .partition(|&time| time < cycle.start as u64);
         Self {
-            primes: vec![
-                2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79,
-                83, 89, 97,
-            ],
-            index: 0,
-
-            // these are based on starting at 97 (6*16 + 1)
-            counters: [2, 6, 9, 6, 12, 2, 5, 10],
-            // (we check (6*16 + 5) next
-            skip_four: true,
-
-            // this starts after the primes we keep counters for
-            check_to_index: CHECK_STARTING_INDEX,
-            check_safe_until: 841,
+            once,
+            repeating,
+            period: cycle.length as u64,
         }
     }
-}
-
-impl Iterator for PrimeIterator {
-    type Item = u32;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let index = self.index;
-        if index < self.primes.len() {
-            self.index += 1;
-            return Some(self.primes[index]);
-        }
-
-        let mut maybe_prime = self.primes[index - 1];
-        loop {
-            // all primes are (6n + 1) or (6n + 5), so alternate +2 and +4
-            let inc: u8 = 2 + 2 * u8::from(self.skip_four);
-            self.skip_four = !self.skip_four;
-
-            maybe_prime += u32::from(inc);
-
-            let mut is_composite = false;
-            for (p, modulus) in COUNTER_PRIMES.iter().zip(self.counters.iter_mut()) {
-                *modulus += inc;
-                if *modulus >= *p {
-                    *modulus -= p;
-                    is_composite = is_composite || *modulus == 0;
-                }
-            }
-
-            if is_composite {
-                continue;
-            }
 
-            if maybe_prime > self.check_safe_until {
-                self.check_to_index += 1;
-                let highest_check_prime = self.primes[self.check_to_index];
-                self.check_safe_until = highest_check_prime.saturating_mul(highest_check_prime);
-            }
-
-            for p in &self.primes[CHECK_STARTING_INDEX..=self.check_to_index] {
-                is_composite = is_composite || maybe_prime % p == 0;
-            }
-
-            if is_composite {
-                continue;
-            }
-
-            self.primes.push(maybe_prime);
-            self.index += 1;
-            break Some(maybe_prime);
-        }
+    fn is_end(&self, time: u64) -> bool {
+        self.once.contains(&time)
+            || self
+                .repeating
+                .iter()
+                .any(|&start| time >= start && (time - start).is_multiple_of(self.period))
     }
 }
 
-#[derive(Debug)]
-struct StepsToEndIter {
-    steps_to_ends: Vec<u32>,
-    loop_to_index: usize,
-    i: usize,
-}
-
-impl StepsToEndIter {
-    fn new(steps_to_ends: Vec<u32>, loop_to_index: usize) -> Self {
-        Self {
-            steps_to_ends,
-            loop_to_index,
-            i: 0,
-        }
+/// Returns the first time every ghost is on an end node at once.
+fn first_common_end(ghosts: &[EndTimes]) -> Option<u64> {
+    // times that don't repeat can simply be checked against every ghost
+    let once = ghosts
+        .iter()
+        .flat_map(|ghost| &ghost.once)
+        .copied()
+        .filter(|&time| ghosts.iter().all(|ghost| ghost.is_end(time)));
+
+    // for the repeating ones, every combination of one repeating end per ghost is a
+    // system of congruences
+    let mut combinations = vec![(0i64, 1i64, 0u64)];
+    for ghost in ghosts {
+        let period = i64::try_from(ghost.period).ok()?;
+        combinations = combinations
+            .into_iter()
+            .flat_map(|(r, m, earliest)| {
+                ghost.repeating.iter().filter_map(move |&start| {
+                    let residue = i64::try_from(start).ok()? % period;
+                    let (r, m) = math::crt([(r, m), (residue, period)])?;
+                    Some((r, m, earliest.max(start)))
+                })
+            })
+            .collect();
     }
-}
+    let repeating = combinations.into_iter().map(|(r, m, earliest)| {
+        let (r, m) = (r.unsigned_abs(), m.unsigned_abs());
+        // the smallest solution that every ghost has already reached
+        r + earliest.saturating_sub(r).div_ceil(m) * m
+    });
 
-impl Iterator for StepsToEndIter {
-    type Item = u32;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let last = self.steps_to_ends.len() - 1;
-        if self.i > last {
-            return None;
-        }
-        let i = self.i;
-        if self.i == last {
-            self.i = self.loop_to_index;
-        } else {
-            self.i += 1;
-        }
-        Some(self.steps_to_ends[i])
-    }
+    once.chain(repeating).min()
 }
 
-const MASK: u32 = u32::from_ne_bytes([0, 0, 0, !0]);
-const START_MASKED: u32 = u32::from_ne_bytes([0, 0, 0, b'A']);
-const END_MASKED: u32 = u32::from_ne_bytes([0, 0, 0, b'Z']);
 pub fn part_two(input: &str) -> Option<u64> {
-    let input = input.as_bytes();
-    let mut iter = input.splitn(2, |&c| c == b'\n');
-    let instructions = iter.next()?;
-    let mut input = iter.next()?.iter();
-    input.next();
-    let mut positions: Vec<_> = Default::default();
-    let map: HashMap<_, _> = std::iter::from_fn(|| read_node(&mut input))
-        .inspect(|(ident, _)| {
-            if ident & MASK == START_MASKED {
-                positions.push(*ident);
-            }
-        })
-        .collect();
+    let network = read_network(input)?;
+    let instructions = network.instructions;
+    if instructions.is_empty() {
+        return None;
+    }
+    let ends_with = |letter| move |&(_, name): &(usize, &[u8])| name.last() == Some(&letter);
+    let mut is_end = vec![false; network.names.len()];
+    for (id, _) in network.names.iter().filter(ends_with(b'Z')) {
+        is_end[id] = true;
+    }
 
-    let instruction_steps = instructions.len() as u32;
-    let loop_data: Vec<_> = positions
-        .into_iter()
-        .map(|mut position| {
-            let mut steps = 0;
-            let mut end_data = vec![];
-            for turn in instructions.iter().cycle() {
-                position = match (turn, map.get(&position)) {
-                    (b'L', Some((ident, _))) | (b'R', Some((_, ident))) => *ident,
-                    _ => panic!("position not found in map"),
-                };
-                steps += 1;
-                if position & MASK == END_MASKED {
-                    let mut cycle_steps = steps;
-                    let mut loop_to_index = None;
-                    for (i, (datum_steps, end_pos)) in end_data.iter().enumerate().rev() {
-                        if *end_pos == position && cycle_steps % instruction_steps == 0 {
-                            // we've found a full cycle.
-                            loop_to_index = Some(i + 1);
-                            break;
-                        }
-                        cycle_steps += *datum_steps;
-                    }
-                    end_data.push((steps, position));
-                    if let Some(loop_to_index) = loop_to_index {
-                        let steps_only: Vec<_> =
-                            end_data.into_iter().map(|(steps, _)| steps).collect();
-                        return (steps_only, loop_to_index);
-                    }
-                    steps = 0;
-                }
-            }
-            unreachable!();
-        })
-        .collect();
-    if loop_data
+    let starts: Vec<_> = network
+        .names
         .iter()
-        .all(|(steps, _)| steps.windows(2).all(|w| w[0] == w[1]))
-    {
-        // If we have simple loops, just find their lowest common multiple (LCM)
-        let mut loop_data: Vec<_> = loop_data.into_iter().map(|(steps, _)| steps[0]).collect();
-        let mut lcm = 1u64;
-        for prime in PrimeIterator::new() {
-            if loop_data.windows(2).all(|w| w[0] == w[1]) {
-                lcm *= u64::from(loop_data[0]);
-                break;
-            }
-            let mut max_exponent = 0;
-            for step in &mut loop_data {
-                let mut exponent = 0;
-                loop {
-                    let (quo, rem) = (*step / prime, *step % prime);
-                    if rem != 0 {
-                        break;
-                    }
-                    *step = quo;
-                    exponent += 1;
-                }
-                max_exponent = max_exponent.max(exponent);
-            }
-            let prime = u64::from(prime);
-            lcm *= prime.pow(max_exponent);
-        }
-        return Some(lcm);
-    }
-    let mut end_data: Vec<_> = loop_data
-        .into_iter()
-        .map(|(steps_only, loop_to_index)| {
-            StepsToEndIter::new(steps_only, loop_to_index)
-                .scan(0u64, |total, steps| {
-                    *total += u64::from(steps);
-                    Some(*total)
-                })
-                .peekable()
-        })
+        .filter(ends_with(b'A'))
+        .map(|(start, _)| start)
         .collect();
-    loop {
-        let mut all_equal = true;
-        let highest = *end_data
-            .iter_mut()
-            .map(|it| it.peek().unwrap())
-            .reduce(|highest, v| {
-                all_equal = all_equal && v == highest;
-                highest.max(v)
-            })?;
-        if all_equal {
-            break Some(highest);
-        }
-        for it in &mut end_data {
-            while *it.peek().unwrap() < highest {
-                it.next();
-            }
-        }
+    if starts.is_empty() {
+        return None;
     }
+    // every ghost's cycle is found independently
+    let ghosts = parallel::par_map(&starts, |&start| {
+        // a ghost's state is its position and where it is in the instructions, or `None` once
+        // it reaches a node that isn't in the map, which it then stays in
+        let step = |state: &Option<(usize, usize)>| {
+            let (position, i) = (*state)?;
+            let position = network.step(position, instructions[i])?;
+            Some((position, (i + 1) % instructions.len()))
+        };
+        let initial = Some((start, 0));
+        let cycle = cycle::brent(initial, step);
+        let states: Option<Vec<_>> =
+            std::iter::successors(Some(initial), |state| Some(step(state)))
+                .take(cycle.start + cycle.length)
+                .collect();
+        let ends = states?
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (position, _))| is_end[*position])
+            .map(|(time, _)| time as u64);
+        Some(EndTimes::new(ends, cycle))
+    });
+    let ghosts: Option<Vec<_>> = ghosts.into_iter().collect();
+    first_common_end(&ghosts?)
 }
 
 #[cfg(test)]
@@ -336,47 +201,29 @@ mod tests {
     }
 
     #[test]
-    fn test_gcd() {
-        assert_eq!(gcd(2, 4), 2);
-        assert_eq!(gcd(1, 5), 1);
-        assert_eq!(gcd(3, 6), 3);
-        assert_eq!(gcd(4, 12), 4);
-        assert_eq!(gcd(6, 12), 6);
-        assert_eq!(gcd(6, 8), 2);
-
-        // the following tests came from:
-        // https://codereview.stackexchange.com/questions/183211/find-the-greatest-common-divisor-with-unit-tests
-        assert_eq!(gcd(42, 56), 14);
-        assert_eq!(gcd(461952, 116298), 18);
-        assert_eq!(gcd(7966496, 314080416), 32);
-        assert_eq!(gcd(24826148, 45296490), 526);
-        assert_eq!(gcd(12, 0), 12);
-        assert_eq!(gcd(0, 0), 0);
-        assert_eq!(gcd(0, 9), 9);
+    fn test_part_two_invalid() {
+        // `22B` leads to `99Z`, which isn't in the map
+        let input = "L\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)\n22A = (22B, 22B)\n22B = (99Z, 99Z)\n";
+        assert_eq!(part_two(input), None);
+        let input = "LR\n\n11B = (11Z, 11Z)\n11Z = (11B, 11B)\n";
+        assert_eq!(part_two(input), None);
     }
 
     #[test]
-    fn test_prime_iterator() {
-        let mut it = PrimeIterator::new();
-        let result = it.next();
-        assert_eq!(result, Some(2));
-        let result = it.next();
-        assert_eq!(result, Some(3));
-        let mut it = it.skip(22);
-        let result = it.next();
-        assert_eq!(result, Some(97));
-        let result = it.next();
-        assert_eq!(result, Some(101));
-        let result = it.next();
-        assert_eq!(result, Some(103));
-        let mut it = it.skip(22);
-        let result = it.next();
-        assert_eq!(result, Some(229));
-        let mut it = it.skip(1099);
-        let result = it.next();
-        assert_eq!(result, Some(9283));
-        let mut it = it.skip(849);
-        let result = it.next();
-        assert_eq!(result, Some(17389));
+    fn test_first_common_end() {
+        // ends at 2, then every 3 steps from 5
+        let ghost = || {
+            EndTimes::new(
+                [2, 5],
+                Cycle {
+                    start: 3,
+                    length: 3,
+                },
+            )
+        };
+        let other = |ends, start, length| EndTimes::new(ends, Cycle { start, length });
+        assert_eq!(first_common_end(&[ghost(), other([3, 7], 4, 4)]), Some(11));
+        assert_eq!(first_common_end(&[ghost(), other([2, 7], 3, 5)]), Some(2));
+        assert_eq!(first_common_end(&[ghost(), other([1, 7], 2, 6)]), None);
     }
 }
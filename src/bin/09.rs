@@ -1,89 +1,32 @@
+use advent_of_code::parse;
+use advent_of_code::sequence::DifferenceTable;
+
 advent_of_code::solution!(9);
 
-pub fn part_one(input: &str) -> Option<i32> {
+/// Returns the sum of each line's history extrapolated to the index `index` picks for it.
+fn sum_extrapolated(input: &str, index: impl Fn(&DifferenceTable) -> i64) -> Option<i64> {
     let mut input = input.as_bytes().iter();
 
-    let mut total = 0;
+    let mut total = 0i128;
     loop {
-        let mut differences = vec![];
-        let mut number = 0;
-        let mut sign = 1;
-        for c in input.by_ref() {
-            if c.is_ascii_digit() {
-                number *= 10;
-                number += i32::from(*c - b'0');
-                continue;
-            }
-            if *c == b'-' {
-                sign = -1;
-                continue;
-            }
-            number *= sign;
-            let mut push_num = true;
-            for n in &mut differences {
-                if *n == 0 && number == 0 {
-                    push_num = false;
-                    break;
-                }
-                (*n, number) = (number, number - *n);
-            }
-            if push_num {
-                differences.push(number);
-            }
-            if *c == b'\n' {
-                break;
-            }
-            number = 0;
-            sign = 1;
+        let mut numbers = parse::line_numbers::<i64, _>(&mut input);
+        let history: DifferenceTable = numbers.by_ref().collect();
+        if numbers.overflowed() {
+            return None;
         }
-        if let Some(next_term) = differences.into_iter().reduce(|m, n| m + n) {
-            total += next_term;
-        } else {
-            break Some(total);
+        if history.is_empty() {
+            break i64::try_from(total).ok();
         }
+        total = total.checked_add(history.value_at(index(&history))?)?;
     }
 }
 
-pub fn part_two(input: &str) -> Option<i32> {
-    let mut input = input.as_bytes().iter();
-
-    let mut total = 0;
-    loop {
-        let mut leftmost = vec![];
-        let mut differences = vec![];
-        let mut number = 0;
-        let mut sign = 1;
-        for c in input.by_ref() {
-            if c.is_ascii_digit() {
-                number *= 10;
-                number += i32::from(*c - b'0');
-                continue;
-            }
-            if *c == b'-' {
-                sign = -1;
-                continue;
-            }
-            number *= sign;
-
-            for n in &mut differences {
-                (*n, number) = (number, number - *n);
-            }
-            leftmost.push(number);
-            differences.push(number);
-            if *c == b'\n' {
-                break;
-            }
-            number = 0;
-            sign = 1;
-        }
+pub fn part_one(input: &str) -> Option<i64> {
+    sum_extrapolated(input, |history| i64::try_from(history.len()).unwrap())
+}
 
-        let before_first = leftmost.into_iter().rev().reduce(|m, n| n - m);
-        if let Some(before_first) = before_first {
-            total += before_first;
-        } else {
-            break Some(total);
-        }
-    }
+pub fn part_two(input: &str) -> Option<i64> {
+    sum_extrapolated(input, |_| -1)
 }
 
 #[cfg(test)]
@@ -101,4 +44,18 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2));
     }
+
+    #[test]
+    fn test_large_values() {
+        let input = "2000000000 4000000000 6000000000\n";
+        assert_eq!(part_one(input), Some(8_000_000_000));
+        assert_eq!(part_two(input), Some(0));
+    }
+
+    #[test]
+    fn test_value_too_large() {
+        let input = "1 99999999999999999999 3 4\n";
+        assert_eq!(part_one(input), None);
+        assert_eq!(part_two(input), None);
+    }
 }
@@ -1,9 +1,17 @@
-advent_of_code::solution!(11);
+use advent_of_code::bits::BitGrid;
+use advent_of_code::geometry::Point;
+use advent_of_code::grid::Grid;
+use advent_of_code::parallel;
+use advent_of_code::template::rng::Rng;
 
-fn make_expansion_record(should_expand: Vec<bool>) -> Vec<(usize, usize)> {
+advent_of_code::solution!(11, generator: generate);
+
+fn make_expansion_record(
+    should_expand: impl ExactSizeIterator<Item = bool>,
+) -> Vec<(usize, usize)> {
     let mut expansion_record = vec![];
     let should_length = should_expand.len();
-    let mut iter = should_expand.into_iter();
+    let mut iter = should_expand;
     let mut expansion = 0usize;
     let mut total_position = 0;
     while let Some(pos) = iter.position(|x| x) {
@@ -21,50 +29,69 @@ fn make_expansion_record(should_expand: Vec<bool>) -> Vec<(usize, usize)> {
     expansion_record
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let input = input.as_bytes();
-    let iter = input.iter();
-    let mut col_should_expand = vec![];
-    let mut row_should_expand = vec![true];
-    let mut galaxies = vec![];
-    let mut row = 0;
-    let mut col = 0;
-    let mut width = None;
-    for c in iter {
-        if width.is_none() {
-            col_should_expand.push(true);
-        }
-        match c {
-            b'\n' => {
-                if width.is_none() {
-                    width = Some(col);
-                    col_should_expand.pop();
-                }
-                row_should_expand.push(true);
-                row += 1;
-                col = 0;
-                continue;
-            }
-            b'#' => {
-                galaxies.push((col, row));
-                row_should_expand[row] = false;
-                col_should_expand[col] = false;
-            }
-            _ => {}
-        }
-        col += 1;
-    }
-    let Some(_width) = width else {
+/// Sums the distances between every pair of galaxies. Each galaxy is paired with the ones
+/// before it, so later galaxies have more pairs; the threads take every `threads`-th galaxy
+/// in turn to get an even share. On one thread this is a plain loop over the galaxies.
+/// Returns [`None`] if the sum doesn't fit in a `u64`.
+fn sum_of_distances(galaxies: &[Point<u64>]) -> Option<u64> {
+    let threads = parallel::threads();
+    let offsets: Vec<usize> = (0..threads).collect();
+    parallel::par_reduce(
+        &offsets,
+        || Some(0u64),
+        |total, &offset| {
+            let distance = |g1: Point<u64>, g2: Point<u64>| {
+                g1.x.abs_diff(g2.x).checked_add(g1.y.abs_diff(g2.y))
+            };
+            let pairs = |pos: usize| {
+                galaxies[..pos].iter().try_fold(0u64, |sum, &g2| {
+                    sum.checked_add(distance(galaxies[pos], g2)?)
+                })
+            };
+            let share = (offset..galaxies.len())
+                .step_by(threads)
+                .try_fold(0u64, |sum, pos| sum.checked_add(pairs(pos)?));
+            total?.checked_add(share?)
+        },
+        |a, b| a?.checked_add(b?),
+    )
+}
+
+struct Image {
+    galaxies: Vec<Point<usize>>,
+    col_expansion: Vec<(usize, usize)>,
+    row_expansion: Vec<(usize, usize)>,
+}
+
+fn read_image(input: &str) -> Option<Image> {
+    let grid = Grid::parse(input).ok()?;
+    if grid.height() == 0 {
         return None;
-    };
-    row_should_expand.pop();
-    let col_expansion = make_expansion_record(col_should_expand);
-    let row_expansion = make_expansion_record(row_should_expand);
+    }
+    let occupied = BitGrid::from_grid(&grid, |&c| c == b'#');
+    let galaxies = occupied.iter_ones().map(Point::from).collect();
+    let any_in_columns = occupied.any_in_columns();
+    let col_should_expand =
+        (0..grid.width()).map(|col| any_in_columns[col / 64] >> (col % 64) & 1 == 0);
+    let row_should_expand = (0..grid.height()).map(|row| occupied.row_ones(row) == 0);
+    Some(Image {
+        galaxies,
+        col_expansion: make_expansion_record(col_should_expand),
+        row_expansion: make_expansion_record(row_should_expand),
+    })
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    let Image {
+        mut galaxies,
+        col_expansion,
+        row_expansion,
+    } = read_image(input)?;
     let mut col_ei = 0;
     let mut row_ei = 0;
 
     for g in &mut galaxies {
-        let (col, row) = *g;
+        let Point { x: col, y: row } = *g;
         if col_expansion[col_ei].0 > col {
             col_ei = 0;
         }
@@ -78,69 +105,32 @@ pub fn part_one(input: &str) -> Option<u32> {
             row_ei += 1;
         }
         row_ei -= 1;
-        *g = (col + col_expansion[col_ei].1, row + row_expansion[row_ei].1);
+        *g = Point::new(col + col_expansion[col_ei].1, row + row_expansion[row_ei].1);
     }
 
-    Some(
-        galaxies
-            .iter()
-            .enumerate()
-            .map(|(pos, (col1, row1))| {
-                galaxies
-                    .iter()
-                    .take(pos)
-                    .map(|(col2, row2)| col1.abs_diff(*col2) + row1.abs_diff(*row2))
-                    .sum::<usize>()
-            })
-            .sum::<usize>() as u32,
-    )
+    let galaxies: Option<Vec<_>> = galaxies.iter().map(|&g| g.try_cast()).collect();
+    sum_of_distances(&galaxies?)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let input = input.as_bytes();
-    let iter = input.iter();
-    let mut col_should_expand = vec![];
-    let mut row_should_expand = vec![true];
-    let mut galaxies = vec![];
-    let mut row = 0;
-    let mut col = 0;
-    let mut width = None;
-    for c in iter {
-        if width.is_none() {
-            col_should_expand.push(true);
-        }
-        match c {
-            b'\n' => {
-                if width.is_none() {
-                    width = Some(col);
-                    col_should_expand.pop();
-                }
-                row_should_expand.push(true);
-                row += 1;
-                col = 0;
-                continue;
-            }
-            b'#' => {
-                galaxies.push((col, row));
-                row_should_expand[row] = false;
-                col_should_expand[col] = false;
-            }
-            _ => {}
-        }
-        col += 1;
-    }
-    let Some(_width) = width else {
-        return None;
-    };
-    row_should_expand.pop();
-    let col_expansion = make_expansion_record(col_should_expand);
-    let row_expansion = make_expansion_record(row_should_expand);
+    let Image {
+        galaxies,
+        col_expansion,
+        row_expansion,
+    } = read_image(input)?;
     let mut col_ei = 0;
     let mut row_ei = 0;
 
-    let expansion_factor = 1_000_000;
+    let expansion_factor: u64 = 1_000_000;
+    // each empty row or column before a position adds `expansion_factor - 1`
+    let expand = |pos: usize, empty: usize| {
+        let extra = u64::try_from(empty)
+            .ok()?
+            .checked_mul(expansion_factor - 1)?;
+        u64::try_from(pos).ok()?.checked_add(extra)
+    };
 
-    let galaxies: Vec<_> = galaxies.into_iter().map(|(col, row)| {
+    let galaxies: Option<Vec<_>> = galaxies.into_iter().map(|Point { x: col, y: row }| {
         if col_expansion[col_ei].0 > col {
             col_ei = 0;
         }
@@ -154,22 +144,29 @@ pub fn part_two(input: &str) -> Option<u64> {
             row_ei += 1;
         }
         row_ei -= 1;
-        ((col + col_expansion[col_ei].1 * (expansion_factor - 1)) as u64, (row + row_expansion[row_ei].1 * (expansion_factor - 1)) as u64)
+        Some(Point::new(expand(col, col_expansion[col_ei].1)?, expand(row, row_expansion[row_ei].1)?))
     }).collect();
 
-    Some(
-        galaxies
-            .iter()
-            .enumerate()
-            .map(|(pos, (col1, row1))| {
-                galaxies
-                    .iter()
-                    .take(pos)
-                    .map(|(col2, row2)| col1.abs_diff(*col2) + row1.abs_diff(*row2))
-                    .sum::<u64>()
-            })
-            .sum::<u64>(),
-    )
+    sum_of_distances(&galaxies?)
+}
+
+/// Generates a square image with `size` galaxies, covering about 1 in 16 tiles.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let width = (size * 16).isqrt() + 2;
+    let mut image = vec![b'.'; width * width];
+    let mut cells: Vec<_> = (0..image.len()).collect();
+    rng.shuffle(&mut cells);
+    for &cell in cells.iter().take(size) {
+        image[cell] = b'#';
+    }
+
+    let mut input = String::with_capacity(width * (width + 1));
+    for row in image.chunks(width) {
+        input.push_str(std::str::from_utf8(row).unwrap());
+        input.push('\n');
+    }
+    input
 }
 
 #[cfg(test)]
@@ -185,6 +182,14 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(82000210));
+        assert_eq!(result, Some(82_000_210));
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(100, 1);
+        assert_eq!(input.matches('#').count(), 100);
+        assert_eq!(input, generate(100, 1));
+        assert!(part_two(&input).is_some());
     }
 }
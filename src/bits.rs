@@ -0,0 +1,494 @@
+/// Module with bit-packed sets: a `BitSet` for small universes and a `BitGrid` of on/off cells,
+/// which combines, shifts and counts whole rows 64 cells at a time.
+use std::fmt::Display;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub};
+
+use crate::geometry::Dir4;
+use crate::grid::{Grid, Pos};
+
+/// A set of the numbers `0..64 * N`, stored as `N` words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<const N: usize> {
+    words: [u64; N],
+}
+
+impl<const N: usize> Default for BitSet<N> {
+    fn default() -> Self {
+        Self { words: [0; N] }
+    }
+}
+
+impl<const N: usize> BitSet<N> {
+    /// The numbers the set can hold are `0..CAPACITY`.
+    pub const CAPACITY: usize = 64 * N;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value`, returning whether it was new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not below [`Self::CAPACITY`].
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, 1 << (value % 64));
+        let new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        new
+    }
+
+    /// Removes `value`, returning whether it was there.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let Some(word) = self.words.get_mut(value / 64) else {
+            return false;
+        };
+        let bit = 1 << (value % 64);
+        let found = *word & bit != 0;
+        *word &= !bit;
+        found
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / 64)
+            .is_some_and(|word| word >> (value % 64) & 1 == 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words = [0; N];
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(a, b)| a & !b == 0)
+    }
+
+    /// Returns the numbers in the set in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..)
+            .zip(self.words)
+            .flat_map(|(index, word)| ones(word, 64 * index))
+    }
+}
+
+/// Returns the positions of the set bits of `word`, plus `offset`, in ascending order.
+fn ones(mut word: u64, offset: usize) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if word == 0 {
+            return None;
+        }
+        let bit = word.trailing_zeros() as usize;
+        word &= word - 1;
+        Some(offset + bit)
+    })
+}
+
+impl<const N: usize> FromIterator<usize> for BitSet<N> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const N: usize> Extend<usize> for BitSet<N> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+macro_rules! impl_set_op {
+    ($($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, |$a:ident, $b:ident| $combine:expr;)*) => {$(
+        impl<const N: usize> $op_assign for BitSet<N> {
+            fn $method_assign(&mut self, other: Self) {
+                for ($a, &$b) in self.words.iter_mut().zip(&other.words) {
+                    *$a = $combine;
+                }
+            }
+        }
+
+        impl<const N: usize> $op for BitSet<N> {
+            type Output = Self;
+
+            fn $method(mut self, other: Self) -> Self {
+                self.$method_assign(other);
+                self
+            }
+        }
+
+        impl $op_assign<&Self> for BitGrid {
+            fn $method_assign(&mut self, other: &Self) {
+                assert_eq!(
+                    (self.width, self.height),
+                    (other.width, other.height),
+                    "grids must be the same size"
+                );
+                for ($a, &$b) in self.words.iter_mut().zip(&other.words) {
+                    *$a = $combine;
+                }
+            }
+        }
+
+        impl $op for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, other: Self) -> BitGrid {
+                let mut result = self.clone();
+                result.$method_assign(other);
+                result
+            }
+        }
+    )*};
+}
+
+impl_set_op!(
+    BitOr, bitor, BitOrAssign, bitor_assign, |a, b| *a | b;
+    BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| *a & b;
+    BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| *a ^ b;
+);
+
+/// The numbers in `self` but not in `other`.
+impl<const N: usize> Sub for BitSet<N> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        for (a, b) in self.words.iter_mut().zip(other.words) {
+            *a &= !b;
+        }
+        self
+    }
+}
+
+/// A grid of on/off cells, 64 to a word. Positions are `(row, col)` like in a [`Grid`].
+///
+/// Cells outside the grid read as off, and shifting moves cells off the edges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    /// Words per row; each row starts on a new word.
+    stride: usize,
+}
+
+impl BitGrid {
+    /// Creates a grid with every cell off.
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        Self {
+            words: vec![0; stride * height],
+            width,
+            height,
+            stride,
+        }
+    }
+
+    /// Creates a grid the size of `grid` with the cells for which `is_on` returns `true` on.
+    pub fn from_grid<T>(grid: &Grid<T>, mut is_on: impl FnMut(&T) -> bool) -> Self {
+        let mut bits = Self::new(grid.width(), grid.height());
+        for (pos, cell) in grid.iter() {
+            if is_on(cell) {
+                bits.set(pos, true);
+            }
+        }
+        bits
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The mask of the bits in the last word of each row that are inside the grid.
+    fn tail_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => !0,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    /// Returns the words of `row`; bit `col % 64` of word `col / 64` is the cell at `col`.
+    pub fn row_words(&self, row: usize) -> &[u64] {
+        &self.words[row * self.stride..(row + 1) * self.stride]
+    }
+
+    /// Returns whether the cell at `pos` is on, which it never is outside the grid.
+    pub fn get(&self, pos: impl Into<Pos>) -> bool {
+        let (row, col) = pos.into();
+        row < self.height
+            && col < self.width
+            && self.words[row * self.stride + col / 64] >> (col % 64) & 1 == 1
+    }
+
+    /// Turns the cell at `pos` on or off.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is outside the grid.
+    pub fn set(&mut self, pos: impl Into<Pos>, on: bool) {
+        let (row, col) = pos.into();
+        assert!(
+            row < self.height && col < self.width,
+            "{:?} is outside of the {}x{} grid",
+            (row, col),
+            self.width,
+            self.height
+        );
+        let word = &mut self.words[row * self.stride + col / 64];
+        let bit = 1 << (col % 64);
+        if on {
+            *word |= bit;
+        } else {
+            *word &= !bit;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// The number of cells that are on.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// The number of cells in `row` that are on.
+    pub fn row_ones(&self, row: usize) -> usize {
+        self.row_words(row)
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// The number of cells in `col` that are on.
+    pub fn column_ones(&self, col: usize) -> usize {
+        (0..self.height).filter(|&row| self.get((row, col))).count()
+    }
+
+    /// Returns each column, in one word per 64 columns, with the bits set that are on in any
+    /// row.
+    pub fn any_in_columns(&self) -> Vec<u64> {
+        let mut any = vec![0; self.stride];
+        for row in self.words.chunks_exact(self.stride) {
+            for (any, word) in any.iter_mut().zip(row) {
+                *any |= word;
+            }
+        }
+        any
+    }
+
+    /// Returns the positions that are on, row by row.
+    pub fn iter_ones(&self) -> impl Iterator<Item = Pos> + '_ {
+        let stride = self.stride;
+        (0..).zip(&self.words).flat_map(move |(index, &word)| {
+            let (row, first_col) = (index / stride, 64 * (index % stride));
+            ones(word, first_col).map(move |col| (row, col))
+        })
+    }
+
+    /// Returns the grid with every cell moved one step towards `dir`.
+    #[must_use]
+    pub fn shifted(&self, dir: Dir4) -> Self {
+        let mut shifted = Self::new(self.width, self.height);
+        let stride = self.stride;
+        if stride == 0 || self.height == 0 {
+            return shifted;
+        }
+        match dir {
+            Dir4::N => {
+                shifted.words[..self.words.len() - stride].copy_from_slice(&self.words[stride..]);
+            }
+            Dir4::S => {
+                shifted.words[stride..].copy_from_slice(&self.words[..self.words.len() - stride]);
+            }
+            Dir4::E | Dir4::W => {
+                let tail_mask = self.tail_mask();
+                for (to, from) in shifted
+                    .words
+                    .chunks_exact_mut(stride)
+                    .zip(self.words.chunks_exact(stride))
+                {
+                    for i in 0..stride {
+                        to[i] = if dir == Dir4::E {
+                            let carry = if i > 0 { from[i - 1] >> 63 } else { 0 };
+                            from[i] << 1 | carry
+                        } else {
+                            let carry = from.get(i + 1).map_or(0, |next| next << 63);
+                            from[i] >> 1 | carry
+                        };
+                    }
+                    to[stride - 1] &= tail_mask;
+                }
+            }
+        }
+        shifted
+    }
+
+    /// Returns the cells with at least one of their 4 neighbors on.
+    #[must_use]
+    pub fn neighbors4(&self) -> Self {
+        let mut mask = self.shifted(Dir4::N);
+        for dir in [Dir4::E, Dir4::S, Dir4::W] {
+            mask |= &self.shifted(dir);
+        }
+        mask
+    }
+
+    /// Returns the cells with at least one of their 8 neighbors on.
+    #[must_use]
+    pub fn neighbors8(&self) -> Self {
+        let sideways = &self.shifted(Dir4::E) | &self.shifted(Dir4::W);
+        let row = self | &sideways;
+        let mut mask = sideways;
+        mask |= &row.shifted(Dir4::N);
+        mask |= &row.shifted(Dir4::S);
+        mask
+    }
+
+    /// Returns the grid mirrored along its main diagonal.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        let mut transposed = Self::new(self.height, self.width);
+        for (row, col) in self.iter_ones() {
+            transposed.set((col, row), true);
+        }
+        transposed
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut flipped = self.clone();
+        let tail_mask = self.tail_mask();
+        for row in flipped.words.chunks_exact_mut(self.stride.max(1)) {
+            for word in row.iter_mut() {
+                *word = !*word;
+            }
+            if let Some(last) = row.last_mut() {
+                *last &= tail_mask;
+            }
+        }
+        flipped
+    }
+}
+
+/// Prints `#` for the cells that are on and `.` for the others.
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.height {
+            let line: String = (0..self.width)
+                .map(|col| if self.get((row, col)) { '#' } else { '.' })
+                .collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    /// Parses a grid of `#` and `.`.
+    fn bits(input: &str) -> BitGrid {
+        BitGrid::from_grid(&Grid::parse(input).unwrap(), |&c| c == b'#')
+    }
+
+    #[test]
+    fn bit_set() {
+        let mut set: BitSet<2> = [3, 64, 127].into_iter().collect();
+        assert_eq!(BitSet::<2>::CAPACITY, 128);
+        assert!(set.contains(64));
+        assert!(!set.contains(65));
+        assert!(!set.contains(1000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [3, 64, 127]);
+        assert!(set.remove(64));
+        assert!(!set.remove(64));
+        assert!(!set.remove(1000));
+        assert_eq!(set.min(), Some(3));
+
+        let other: BitSet<2> = [3, 4].into_iter().collect();
+        assert_eq!((set | other).len(), 3);
+        assert_eq!((set & other).iter().collect::<Vec<_>>(), [3]);
+        assert_eq!((set ^ other).iter().collect::<Vec<_>>(), [4, 127]);
+        assert_eq!((set - other).iter().collect::<Vec<_>>(), [127]);
+        assert!((set & other).is_subset(&set));
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.min(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bit_set_capacity() {
+        BitSet::<1>::new().insert(64);
+    }
+
+    #[test]
+    fn shifts_across_words() {
+        // 70 columns, so each row takes two words
+        let mut grid = BitGrid::new(70, 3);
+        for pos in [(0, 0), (1, 63), (1, 64), (2, 69)] {
+            grid.set(pos, true);
+        }
+        let moved = |dir| grid.shifted(dir).iter_ones().collect::<Vec<_>>();
+        assert_eq!(moved(Dir4::E), [(0, 1), (1, 64), (1, 65)]);
+        assert_eq!(moved(Dir4::W), [(1, 62), (1, 63), (2, 68)]);
+        assert_eq!(moved(Dir4::N), [(0, 63), (0, 64), (1, 69)]);
+        assert_eq!(moved(Dir4::S), [(1, 0), (2, 63), (2, 64)]);
+        assert_eq!(grid.row_ones(1), 2);
+        assert_eq!(grid.column_ones(69), 1);
+        assert_eq!(grid.any_in_columns(), [1 | 1 << 63, 1 | 1 << 5]);
+        assert_eq!((!&grid).count_ones(), 70 * 3 - 4);
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(grid.transpose().iter_ones().last(), Some((69, 2)));
+    }
+
+    #[test]
+    fn neighbor_masks() {
+        let grid = bits(".....\n.....\n..#..\n.....\n.....");
+        assert_eq!(
+            grid.neighbors4().to_string(),
+            ".....\n..#..\n.#.#.\n..#..\n.....\n"
+        );
+        assert_eq!(
+            grid.neighbors8().to_string(),
+            ".....\n.###.\n.#.#.\n.###.\n.....\n"
+        );
+        let corner = bits("#.\n..");
+        assert_eq!(corner.neighbors8().count_ones(), 3);
+
+        let other = bits("..#..\n.....\n..#..\n.....\n.....");
+        assert_eq!((&grid & &other).count_ones(), 1);
+        assert_eq!((&grid ^ &other).iter_ones().collect::<Vec<_>>(), [(0, 2)]);
+        assert!(BitGrid::new(0, 0).neighbors8().iter_ones().next().is_none());
+    }
+}
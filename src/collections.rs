@@ -0,0 +1,183 @@
+/// Module with the Fx hasher (faster than `SipHash` for small keys) and an interner that turns
+/// names into dense indices.
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// A [`HashMap`] using [`FxHasher`].
+pub type FastHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
+/// A [`HashSet`] using [`FxHasher`].
+pub type FastHashSet<T> = HashSet<T, FxBuildHasher>;
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// The Fx hash from Firefox and rustc: each word is mixed in with a rotate, an xor and a
+/// multiply. It is fast and good enough for integers and short strings, but easy to attack.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in chunks.by_ref() {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let mut rest = chunks.remainder();
+        if rest.len() >= 4 {
+            self.add(u64::from(u32::from_le_bytes(rest[..4].try_into().unwrap())));
+            rest = &rest[4..];
+        }
+        if rest.len() >= 2 {
+            self.add(u64::from(u16::from_le_bytes(rest[..2].try_into().unwrap())));
+            rest = &rest[2..];
+        }
+        if let Some(&byte) = rest.first() {
+            self.add(u64::from(byte));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Hands out dense indices `0, 1, 2, ...` for names, in the order they are first seen, and
+/// remembers the names to display them again.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    ids: FastHashMap<Box<[u8]>, usize>,
+    names: Vec<Box<[u8]>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of `name`, giving it the next free one if it is new.
+    pub fn intern(&mut self, name: &[u8]) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.into());
+        self.ids.insert(name.into(), id);
+        id
+    }
+
+    /// Returns the index of `name` if it has been interned.
+    pub fn get(&self, name: &[u8]) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the name with index `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` wasn't handed out by this interner.
+    pub fn name(&self, id: usize) -> &[u8] {
+        &self.names[id]
+    }
+
+    /// Returns the name with index `id` as text, for printing.
+    pub fn display(&self, id: usize) -> std::borrow::Cow<'_, str> {
+        String::from_utf8_lossy(self.name(id))
+    }
+
+    /// The number of distinct names, which is also the next index.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns each index with its name, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &[u8])> + '_ {
+        self.names.iter().map(AsRef::as_ref).enumerate()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasher, Hash};
+
+    fn hash(value: impl Hash) -> u64 {
+        FxBuildHasher::default().hash_one(value)
+    }
+
+    #[test]
+    fn hasher() {
+        assert_eq!(hash(0u64), 0);
+        assert_eq!(hash(1u64), SEED);
+        assert_eq!(hash(1u32), hash(1u64));
+        assert_ne!(hash(b"AAA"), hash(b"AAB"));
+        // every tail length goes through a different branch
+        let long = b"abcdefghijklmno";
+        let hashes: FastHashSet<u64> = (0..=long.len()).map(|len| hash(&long[..len])).collect();
+        assert_eq!(hashes.len(), long.len() + 1);
+
+        let mut map = FastHashMap::default();
+        for i in 0..1000u32 {
+            *map.entry(i % 7).or_insert(0) += 1;
+        }
+        assert_eq!(map[&3], 143);
+    }
+
+    #[test]
+    fn interner() {
+        let mut names = Interner::new();
+        assert!(names.is_empty());
+        let ids: Vec<_> = [b"BBB", b"AAA", b"BBB", b"CCC"]
+            .iter()
+            .map(|name| names.intern(*name))
+            .collect();
+        assert_eq!(ids, [0, 1, 0, 2]);
+        assert_eq!(names.len(), 3);
+        assert_eq!(names.get(b"CCC"), Some(2));
+        assert_eq!(names.get(b"DDD"), None);
+        assert_eq!(names.name(1), b"AAA");
+        assert_eq!(names.display(2), "CCC");
+        assert_eq!(names.intern(b""), 3);
+        let all: Vec<_> = names.iter().collect();
+        assert_eq!(all[..2], [(0, &b"BBB"[..]), (1, &b"AAA"[..])]);
+    }
+}
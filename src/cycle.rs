@@ -0,0 +1,158 @@
+/// Module that detects cycles in states fed through a step function, keeping two states at a time.
+use std::iter;
+
+/// Where the states start repeating, counted in steps from the initial state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// How many steps it takes to first reach a state that is part of the cycle.
+    pub start: usize,
+    /// How many steps it takes to get back to a state in the cycle.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Returns the smallest number of steps that reaches the same state as `steps` does.
+    pub fn reduce(&self, steps: u64) -> usize {
+        let start = self.start as u64;
+        if steps < start {
+            return steps as usize;
+        }
+        self.start + ((steps - start) % self.length as u64) as usize
+    }
+}
+
+/// Finds the cycle using Floyd's tortoise and hare.
+pub fn floyd<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // the hare is now a multiple of the cycle length ahead, so moving both at the same speed
+    // they meet where the cycle starts.
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Finds the cycle using Brent's algorithm, which usually takes fewer steps than [`floyd`].
+pub fn brent<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // find the length by teleporting the tortoise to the hare at each power of two.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // then start a hare that is one cycle length ahead; they meet where the cycle starts.
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Returns the state after `steps` steps, skipping over whole cycles so `steps` can be huge.
+pub fn nth<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S, steps: u64) -> S {
+    let steps = brent(initial.clone(), &mut step).reduce(steps);
+    iter::repeat_n((), steps).fold(initial, |state, ()| step(&state))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    /// A `rho` shaped sequence: `start` states leading into a loop of `length` states.
+    fn rho(start: usize, length: usize) -> impl FnMut(&usize) -> usize {
+        move |&n| {
+            if n + 1 == start + length {
+                start
+            } else {
+                n + 1
+            }
+        }
+    }
+
+    fn counting_collatz(steps: &mut usize) -> impl FnMut(&u64) -> u64 + '_ {
+        move |&n| {
+            *steps += 1;
+            if n % 2 == 0 {
+                n / 2
+            } else {
+                3 * n + 1
+            }
+        }
+    }
+
+    #[test]
+    fn detects_cycles() {
+        for start in 0..10 {
+            for length in 1..10 {
+                let expected = Cycle { start, length };
+                assert_eq!(floyd(0, rho(start, length)), expected);
+                assert_eq!(brent(0, rho(start, length)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn brent_takes_fewer_steps() {
+        let (mut floyd_steps, mut brent_steps) = (0, 0);
+        let expected = Cycle {
+            start: 109,
+            length: 3,
+        };
+        assert_eq!(floyd(27, counting_collatz(&mut floyd_steps)), expected);
+        assert_eq!(brent(27, counting_collatz(&mut brent_steps)), expected);
+        assert!(brent_steps < floyd_steps, "{brent_steps} {floyd_steps}");
+    }
+
+    #[test]
+    fn jumps_ahead() {
+        let cycle = Cycle {
+            start: 5,
+            length: 3,
+        };
+        assert_eq!(cycle.reduce(4), 4);
+        assert_eq!(cycle.reduce(5), 5);
+        assert_eq!(cycle.reduce(8), 5);
+        assert_eq!(cycle.reduce(u64::MAX), 5 + ((u64::MAX - 5) % 3) as usize);
+
+        for steps in 0..40 {
+            let slow = (0..steps).fold(0, |n, _| rho(7, 4)(&n));
+            assert_eq!(nth(0, rho(7, 4), steps), slow);
+        }
+        // 10^15 - 7 leaves 1 when divided by 4
+        assert_eq!(nth(0, rho(7, 4), 1_000_000_000_000_000), 8);
+    }
+}
@@ -2,6 +2,8 @@ use std::error::Error;
 use std::fmt::Display;
 use std::str::FromStr;
 
+use crate::template::clock::{Clock, PuzzleDate, SystemClock};
+
 /// A valid day number of advent (i.e. an integer in range 1 to 25).
 ///
 /// # Display
@@ -31,6 +33,22 @@ impl Day {
         Self(day)
     }
 
+    /// Returns the [`Day`] whose puzzle unlocked most recently, if advent is underway.
+    ///
+    /// Uses the puzzle unlock clock (UTC-5), so a new day starts at midnight US-Eastern.
+    pub fn today() -> Option<Self> {
+        Self::today_with(&SystemClock)
+    }
+
+    /// Same as [`Day::today`], but reads the current time from `clock`.
+    pub fn today_with(clock: &impl Clock) -> Option<Self> {
+        let date = PuzzleDate::now(clock);
+        if date.month != 12 {
+            return None;
+        }
+        Self::new(date.day)
+    }
+
     /// Converts the [`Day`] into an [`u8`].
     pub fn into_inner(self) -> u8 {
         self.0
@@ -135,6 +153,26 @@ macro_rules! day {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{all_days, Day};
+    use crate::template::clock::FixedClock;
+
+    #[test]
+    fn today_during_advent() {
+        // 2023-12-08T04:59:59Z, one second before day 8 unlocks.
+        let clock = FixedClock::from_unix_secs(1_702_011_599);
+        assert_eq!(Day::today_with(&clock), Some(Day(7)));
+        let clock = FixedClock::from_unix_secs(1_702_011_600);
+        assert_eq!(Day::today_with(&clock), Some(Day(8)));
+    }
+
+    #[test]
+    fn today_outside_advent() {
+        // 2023-11-30T12:00:00Z
+        let clock = FixedClock::from_unix_secs(1_701_345_600);
+        assert_eq!(Day::today_with(&clock), None);
+        // 2023-12-26T12:00:00Z
+        let clock = FixedClock::from_unix_secs(1_703_592_000);
+        assert_eq!(Day::today_with(&clock), None);
+    }
 
     #[test]
     fn all_days_iterator() {
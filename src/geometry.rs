@@ -0,0 +1,457 @@
+/// Module with directions and points on a 2D plane. The y axis points down like grid rows, so
+/// `Dir4::N` is `(0, -1)`.
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// An integer type that can be used as a [`Point`] coordinate.
+pub trait Coord:
+    Copy + Ord + Default + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    #[must_use]
+    fn wrapping_add(self, other: Self) -> Self;
+    #[must_use]
+    fn wrapping_sub(self, other: Self) -> Self;
+    /// Returns `self + delta`, or [`None`] on overflow.
+    fn checked_offset(self, delta: i8) -> Option<Self>;
+    /// Returns `self + delta`, wrapping on overflow.
+    #[must_use]
+    fn wrapping_offset(self, delta: i8) -> Self;
+    fn to_usize(self) -> Option<usize>;
+    fn from_usize(n: usize) -> Option<Self>;
+
+    /// Returns `|self - other|`.
+    #[must_use]
+    fn abs_diff(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! impl_coord {
+    ($offset:ident, $wrapping_offset:ident: $($t:ty),*) => {$(
+        impl Coord for $t {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                self.checked_sub(other)
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+
+            fn wrapping_sub(self, other: Self) -> Self {
+                self.wrapping_sub(other)
+            }
+
+            fn checked_offset(self, delta: i8) -> Option<Self> {
+                self.$offset(delta.into())
+            }
+
+            fn wrapping_offset(self, delta: i8) -> Self {
+                self.$wrapping_offset(delta.into())
+            }
+
+            fn to_usize(self) -> Option<usize> {
+                self.try_into().ok()
+            }
+
+            fn from_usize(n: usize) -> Option<Self> {
+                n.try_into().ok()
+            }
+        }
+    )*};
+}
+
+impl_coord!(checked_add_signed, wrapping_add_signed: u8, u16, u32, u64, u128, usize);
+impl_coord!(checked_add, wrapping_add: i8, i16, i32, i64, i128, isize);
+
+/// Something that moves a [`Point`] by one step.
+pub trait Direction: Copy {
+    /// The change in `(x, y)` of one step.
+    fn delta(self) -> Point<i8>;
+}
+
+/// One of the 4 orthogonal directions, in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir4 {
+    N,
+    E,
+    S,
+    W,
+}
+
+impl Dir4 {
+    pub const ALL: [Self; 4] = [Self::N, Self::E, Self::S, Self::W];
+
+    /// Turns 90° clockwise.
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// Turns 90° counter-clockwise.
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::N | Self::S)
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        !self.is_vertical()
+    }
+
+    /// Whether the directions are at a right angle to each other.
+    pub fn is_orthogonal(self, other: Self) -> bool {
+        self.is_vertical() != other.is_vertical()
+    }
+
+    /// Parses compass letters (`NESW`), arrows (`^>v<`) and up/down/left/right (`UDLR`).
+    pub fn from_byte(c: u8) -> Option<Self> {
+        match c {
+            b'N' | b'U' | b'^' => Some(Self::N),
+            b'E' | b'R' | b'>' => Some(Self::E),
+            b'S' | b'D' | b'v' => Some(Self::S),
+            b'W' | b'L' | b'<' => Some(Self::W),
+            _ => None,
+        }
+    }
+}
+
+impl Direction for Dir4 {
+    fn delta(self) -> Point<i8> {
+        match self {
+            Self::N => Point::new(0, -1),
+            Self::E => Point::new(1, 0),
+            Self::S => Point::new(0, 1),
+            Self::W => Point::new(-1, 0),
+        }
+    }
+}
+
+/// One of the 8 compass directions, in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    pub const ALL: [Self; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+
+    /// Turns 45° clockwise.
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turns 45° counter-clockwise.
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl Direction for Dir8 {
+    fn delta(self) -> Point<i8> {
+        match self {
+            Self::N => Point::new(0, -1),
+            Self::NE => Point::new(1, -1),
+            Self::E => Point::new(1, 0),
+            Self::SE => Point::new(1, 1),
+            Self::S => Point::new(0, 1),
+            Self::SW => Point::new(-1, 1),
+            Self::W => Point::new(-1, 0),
+            Self::NW => Point::new(-1, -1),
+        }
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Self::ALL[dir as usize * 2]
+    }
+}
+
+/// A point on a 2D plane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Converts both coordinates to another type, or returns [`None`] if either doesn't fit.
+    pub fn try_cast<U>(self) -> Option<Point<U>>
+    where
+        T: TryInto<U>,
+    {
+        Some(Point::new(self.x.try_into().ok()?, self.y.try_into().ok()?))
+    }
+}
+
+impl<T: Coord> Point<T> {
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add(other.x)?,
+            self.y.checked_add(other.y)?,
+        ))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_sub(other.x)?,
+            self.y.checked_sub(other.y)?,
+        ))
+    }
+
+    #[must_use]
+    pub fn wrapping_add(self, other: Self) -> Self {
+        Self::new(self.x.wrapping_add(other.x), self.y.wrapping_add(other.y))
+    }
+
+    #[must_use]
+    pub fn wrapping_sub(self, other: Self) -> Self {
+        Self::new(self.x.wrapping_sub(other.x), self.y.wrapping_sub(other.y))
+    }
+
+    /// Moves one step in `dir`, or returns [`None`] if a coordinate overflows
+    /// (e.g. going north from `y = 0` with unsigned coordinates).
+    pub fn checked_step(self, dir: impl Direction) -> Option<Self> {
+        let delta = dir.delta();
+        Some(Self::new(
+            self.x.checked_offset(delta.x)?,
+            self.y.checked_offset(delta.y)?,
+        ))
+    }
+
+    /// Moves one step in `dir`, wrapping around on overflow.
+    #[must_use]
+    pub fn wrapping_step(self, dir: impl Direction) -> Self {
+        let delta = dir.delta();
+        Self::new(
+            self.x.wrapping_offset(delta.x),
+            self.y.wrapping_offset(delta.y),
+        )
+    }
+
+    /// The points reached by one step in each of the 4 orthogonal directions.
+    pub fn neighbors4(self) -> impl Iterator<Item = Self> {
+        Dir4::ALL
+            .into_iter()
+            .filter_map(move |d| self.checked_step(d))
+    }
+
+    /// The points reached by one step in each of the 8 directions.
+    pub fn neighbors8(self) -> impl Iterator<Item = Self> {
+        Dir8::ALL
+            .into_iter()
+            .filter_map(move |d| self.checked_step(d))
+    }
+
+    /// The distance when moving orthogonally (taxicab distance).
+    pub fn manhattan(self, other: Self) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The distance when diagonal moves are allowed as well (king's move distance).
+    pub fn chebyshev(self, other: Self) -> T {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// The index of this point in a row-major grid of the given width,
+    /// or [`None`] if it is outside of it.
+    pub fn to_index(self, width: usize) -> Option<usize> {
+        let (x, y) = (self.x.to_usize()?, self.y.to_usize()?);
+        (x < width).then_some(y.checked_mul(width)? + x)
+    }
+
+    /// The point at an index of a row-major grid of the given width.
+    pub fn from_index(index: usize, width: usize) -> Option<Self> {
+        Some(Self::new(
+            T::from_usize(index.checked_rem(width)?)?,
+            T::from_usize(index / width)?,
+        ))
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+/// Converts a grid position `(row, col)`.
+impl From<(usize, usize)> for Point<usize> {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self::new(col, row)
+    }
+}
+
+/// Converts to a grid position `(row, col)`.
+impl From<Point<usize>> for (usize, usize) {
+    fn from(point: Point<usize>) -> Self {
+        (point.y, point.x)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns() {
+        assert_eq!(Dir4::N.turn_right(), Dir4::E);
+        assert_eq!(Dir4::N.turn_left(), Dir4::W);
+        assert_eq!(Dir4::W.turn_right(), Dir4::N);
+        assert_eq!(Dir4::E.reverse(), Dir4::W);
+        assert!(Dir4::N.is_orthogonal(Dir4::E));
+        assert!(!Dir4::N.is_orthogonal(Dir4::S));
+        assert_eq!(Dir8::N.turn_left(), Dir8::NW);
+        assert_eq!(Dir8::NE.reverse(), Dir8::SW);
+        assert_eq!(Dir8::from(Dir4::W), Dir8::W);
+        for dir in Dir8::ALL {
+            assert_eq!(dir.reverse().delta(), -dir.delta());
+            assert_eq!(dir.turn_right().turn_left(), dir);
+        }
+    }
+
+    #[test]
+    fn steps() {
+        let origin = Point::new(0u32, 0);
+        assert_eq!(origin.checked_step(Dir4::W), None);
+        assert_eq!(origin.checked_step(Dir8::SE), Some(Point::new(1, 1)));
+        assert_eq!(origin.wrapping_step(Dir4::N), Point::new(0, u32::MAX));
+        assert_eq!(origin.neighbors4().count(), 2);
+        assert_eq!(Point::new(5i64, 5).neighbors8().count(), 8);
+        assert_eq!(
+            Point::new(-1i8, 0).checked_step(Dir4::W),
+            Some(Point::new(-2, 0))
+        );
+        assert_eq!(Point::new(i8::MIN, 0).checked_step(Dir4::W), None);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Point::new(3i32, -4);
+        let b = Point::new(1, 2);
+        assert_eq!(a + b, Point::new(4, -2));
+        assert_eq!(a - b, Point::new(2, -6));
+        assert_eq!(a * 2, Point::new(6, -8));
+        assert_eq!(Point::new(1u8, 1).checked_sub(Point::new(2, 0)), None);
+        assert_eq!(
+            Point::new(255u8, 0).wrapping_add(Point::new(1, 1)),
+            Point::new(0, 1)
+        );
+        assert_eq!(
+            Point::new(3usize, 4).try_cast::<i64>(),
+            Some(Point::new(3, 4))
+        );
+        assert_eq!(Point::new(-1i32, 4).try_cast::<usize>(), None);
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point::new(1u64, 6);
+        let b = Point::new(5, 11);
+        assert_eq!(a.manhattan(b), 9);
+        assert_eq!(b.manhattan(a), 9);
+        assert_eq!(a.chebyshev(b), 5);
+        assert_eq!(Point::new(-3i32, 0).manhattan(Point::new(3, -1)), 7);
+    }
+
+    #[test]
+    fn grid_indices() {
+        let p = Point::new(2usize, 3);
+        assert_eq!(p.to_index(5), Some(17));
+        assert_eq!(p.to_index(2), None);
+        assert_eq!(Point::from_index(17, 5), Some(p));
+        assert_eq!(Point::<i32>::new(-1, 0).to_index(5), None);
+        assert_eq!(Point::<u8>::from_index(1000, 1), None);
+        assert_eq!(<(usize, usize)>::from(p), (3, 2));
+        assert_eq!(Point::from((3, 2)), p);
+    }
+}
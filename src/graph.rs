@@ -0,0 +1,450 @@
+/// Module with graph searches over nodes given by a neighbor closure, so edges are never stored.
+use crate::math::Int;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// The nodes reached so far, each with the index of the node it was reached from.
+struct Visited<N> {
+    nodes: Vec<(N, usize)>,
+    index: HashMap<N, usize>,
+}
+
+impl<N: Hash + Eq + Clone> Visited<N> {
+    fn new(start: N) -> Self {
+        Self {
+            index: HashMap::from([(start.clone(), 0)]),
+            nodes: vec![(start, 0)],
+        }
+    }
+
+    /// Returns the index of `node`, and whether it was reached for the first time.
+    fn visit(&mut self, node: N, parent: usize) -> (usize, bool) {
+        match self.index.entry(node) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let i = self.nodes.len();
+                self.nodes.push((entry.key().clone(), parent));
+                entry.insert(i);
+                (i, true)
+            }
+        }
+    }
+
+    fn set_parent(&mut self, i: usize, parent: usize) {
+        self.nodes[i].1 = parent;
+    }
+
+    /// Returns the nodes from the start to the node at index `i`.
+    fn path(&self, mut i: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[i].0.clone()];
+        while i != 0 {
+            i = self.nodes[i].1;
+            path.push(self.nodes[i].0.clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Returns a shortest path from `start` to a node for which `is_goal` holds, counting every edge
+/// as one step. The path includes both ends.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Hash + Eq + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited::new(start);
+    let mut queue = VecDeque::from([0]);
+    while let Some(i) = queue.pop_front() {
+        let node = visited.nodes[i].0.clone();
+        if is_goal(&node) {
+            return Some(visited.path(i));
+        }
+        for next in neighbors(&node) {
+            if let (next, true) = visited.visit(next, i) {
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Returns the number of steps from `start` to every node reachable from it.
+pub fn bfs_distances<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Hash + Eq + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node] + 1;
+        for next in neighbors(&node) {
+            if let Entry::Vacant(entry) = distances.entry(next.clone()) {
+                entry.insert(distance);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// Returns a cheapest path from `start` to a node for which `is_goal` holds, and its cost.
+/// `neighbors` returns each neighbor with the cost of moving there, which must not be negative.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Hash + Eq + Clone,
+    C: Int,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbors, |_| C::ZERO, is_goal)
+}
+
+/// Like [`dijkstra`], guided by a `heuristic` that estimates the remaining cost from a node.
+///
+/// The path found is only guaranteed to be cheapest if the heuristic never overestimates, and
+/// it is consistent: it can't drop by more than the cost of an edge.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Hash + Eq + Clone,
+    C: Int,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::ZERO, 0))]);
+    let mut visited = Visited::new(start);
+    let mut costs = vec![C::ZERO];
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > costs[i] {
+            continue;
+        }
+        let node = visited.nodes[i].0.clone();
+        if is_goal(&node) {
+            return Some((visited.path(i), cost));
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            let estimate = next_cost + heuristic(&next);
+            let (next, new) = visited.visit(next, i);
+            if new {
+                costs.push(next_cost);
+            } else if next_cost < costs[next] {
+                costs[next] = next_cost;
+                visited.set_parent(next, i);
+            } else {
+                continue;
+            }
+            heap.push(Reverse((estimate, next_cost, next)));
+        }
+    }
+    None
+}
+
+/// Returns the cost of the cheapest path from `start` to every node reachable from it.
+pub fn dijkstra_distances<N, C, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> HashMap<N, C>
+where
+    N: Hash + Eq + Clone,
+    C: Int,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut heap = BinaryHeap::from([Reverse((C::ZERO, 0))]);
+    let mut visited = Visited::new(start);
+    let mut costs = vec![C::ZERO];
+    while let Some(Reverse((cost, i))) = heap.pop() {
+        if cost > costs[i] {
+            continue;
+        }
+        let node = visited.nodes[i].0.clone();
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            let (next, new) = visited.visit(next, i);
+            if new {
+                costs.push(next_cost);
+            } else if next_cost < costs[next] {
+                costs[next] = next_cost;
+            } else {
+                continue;
+            }
+            heap.push(Reverse((next_cost, next)));
+        }
+    }
+    visited
+        .nodes
+        .into_iter()
+        .map(|(node, _)| node)
+        .zip(costs)
+        .collect()
+}
+
+/// Splits `nodes`, and everything reachable from them, into connected components.
+/// `neighbors` should be symmetric: if `b` is a neighbor of `a`, `a` is one of `b`.
+pub fn connected_components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+) -> Vec<Vec<N>>
+where
+    N: Hash + Eq + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut component_of = HashMap::new();
+    let mut components = vec![];
+    for node in nodes {
+        if component_of.contains_key(&node) {
+            continue;
+        }
+        let id = components.len();
+        component_of.insert(node.clone(), id);
+        let mut component = vec![node];
+        let mut i = 0;
+        while let Some(node) = component.get(i) {
+            for next in neighbors(node) {
+                if let Entry::Vacant(entry) = component_of.entry(next.clone()) {
+                    entry.insert(id);
+                    component.push(next);
+                }
+            }
+            i += 1;
+        }
+        components.push(component);
+    }
+    components
+}
+
+/// Returns `nodes`, and everything reachable from them, ordered so every node comes before its
+/// successors. Returns [`None`] if there is a cycle.
+pub fn toposort<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Option<Vec<N>>
+where
+    N: Hash + Eq + Clone,
+    I: IntoIterator<Item = N>,
+{
+    // nodes that are in `finished` are done; the others in `on_stack` are being visited.
+    let mut on_stack = HashMap::new();
+    let mut finished = vec![];
+    for root in nodes {
+        if on_stack.contains_key(&root) {
+            continue;
+        }
+        on_stack.insert(root.clone(), true);
+        let mut stack = vec![(successors(&root).into_iter(), root)];
+        while let Some((successors_left, _)) = stack.last_mut() {
+            let Some(next) = successors_left.next() else {
+                let (_, node) = stack.pop().unwrap();
+                on_stack.insert(node.clone(), false);
+                finished.push(node);
+                continue;
+            };
+            match on_stack.get(&next) {
+                Some(true) => return None,
+                Some(false) => {}
+                None => {
+                    on_stack.insert(next.clone(), true);
+                    stack.push((successors(&next).into_iter(), next));
+                }
+            }
+        }
+    }
+    finished.reverse();
+    Some(finished)
+}
+
+/// Returns a most expensive path from `start` to a node for which `is_goal` holds, and its
+/// cost, in a graph without cycles.
+///
+/// Returns [`None`] if no goal is reachable or there is a
+/// cycle reachable from `start`.
+pub fn dag_longest_path<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Hash + Eq + Clone,
+    C: Int,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let order = toposort([start.clone()], |node| {
+        successors(node).into_iter().map(|(next, _)| next)
+    })?;
+    let mut visited = Visited::new(start);
+    let mut costs = vec![C::ZERO];
+    for node in order {
+        let i = visited.index[&node];
+        for (next, step) in successors(&node) {
+            let next_cost = costs[i] + step;
+            match visited.visit(next, i) {
+                (_, true) => costs.push(next_cost),
+                (next, false) if next_cost > costs[next] => {
+                    costs[next] = next_cost;
+                    visited.set_parent(next, i);
+                }
+                _ => {}
+            }
+        }
+    }
+    let best = (0..visited.nodes.len())
+        .filter(|&i| is_goal(&visited.nodes[i].0))
+        .max_by_key(|&i| costs[i])?;
+    Some((visited.path(best), costs[best]))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+    use crate::grid::{Grid, Pos};
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E
+";
+
+    fn open_neighbors(grid: &Grid<u8>) -> impl FnMut(&Pos) -> Vec<Pos> + '_ {
+        |&pos| {
+            grid.neighbors4(pos)
+                .filter(|&next| grid[next] != b'#')
+                .collect()
+        }
+    }
+
+    #[test]
+    fn bfs_on_grid() {
+        let grid = Grid::parse(MAZE).unwrap();
+        let start = grid.find(&b'S').unwrap();
+        let end = grid.find(&b'E').unwrap();
+        let path = bfs(start, open_neighbors(&grid), |&pos| pos == end).unwrap();
+        assert_eq!(path.len() - 1, 15);
+        assert_eq!((path[0], path[path.len() - 1]), (start, end));
+        assert!(path
+            .windows(2)
+            .all(|w| grid.neighbors4(w[0]).any(|pos| pos == w[1])));
+
+        let distances = bfs_distances(start, open_neighbors(&grid));
+        assert_eq!(distances[&end], 15);
+        assert_eq!(distances.get(&(0, 3)), None);
+        assert_eq!(
+            bfs(start, open_neighbors(&grid), |&pos| pos == (0, 3)),
+            None
+        );
+        assert_eq!(
+            bfs(start, open_neighbors(&grid), |_| true),
+            Some(vec![start])
+        );
+    }
+
+    #[test]
+    fn weighted_on_grid() {
+        // moving onto a digit costs that digit
+        let grid = Grid::parse("1163\n1381\n2136\n").unwrap();
+        let end = (2, 3);
+        let neighbors = |&pos: &Pos| {
+            grid.neighbors4(pos)
+                .map(|next| (next, u32::from(grid[next] - b'0')))
+                .collect::<Vec<_>>()
+        };
+        let (path, cost) = dijkstra((0, 0), neighbors, |&pos| pos == end).unwrap();
+        assert_eq!(cost, 13);
+        assert_eq!(
+            path.iter()
+                .skip(1)
+                .map(|&pos| u32::from(grid[pos] - b'0'))
+                .sum::<u32>(),
+            cost
+        );
+        let distances = dijkstra_distances((0, 0), neighbors);
+        assert_eq!(distances[&end], 13);
+        assert_eq!(distances[&(0, 0)], 0);
+        assert_eq!(distances.len(), 12);
+
+        let heuristic = |&pos: &Pos| Point::from(pos).manhattan(Point::from(end)) as u32;
+        let (_, cost) = astar((0, 0), neighbors, heuristic, |&pos| pos == end).unwrap();
+        assert_eq!(cost, 13);
+    }
+
+    #[test]
+    fn components_and_order() {
+        // packed labels, like day 08's nodes
+        let label = |name: &[u8; 3]| u32::from_ne_bytes([0, name[0], name[1], name[2]]);
+        let edges = [
+            (b"AAA", b"BBB"),
+            (b"BBB", b"CCC"),
+            (b"AAA", b"CCC"),
+            (b"XXX", b"YYY"),
+        ];
+        let edges: Vec<_> = edges.iter().map(|(a, b)| (label(a), label(b))).collect();
+        let successors = |&node: &u32| {
+            edges
+                .iter()
+                .filter(move |(from, _)| *from == node)
+                .map(|&(_, to)| to)
+        };
+        let undirected = |&node: &u32| {
+            edges
+                .iter()
+                .filter_map(move |&(a, b)| (a == node).then_some(b).or((b == node).then_some(a)))
+        };
+
+        let mut components = connected_components(edges.iter().map(|&(a, _)| a), undirected);
+        components.iter_mut().for_each(|c| c.sort_unstable());
+        components.sort_unstable();
+        let mut expected = vec![
+            vec![label(b"AAA"), label(b"BBB"), label(b"CCC")],
+            vec![label(b"XXX"), label(b"YYY")],
+        ];
+        expected.iter_mut().for_each(|c| c.sort_unstable());
+        expected.sort_unstable();
+        assert_eq!(components, expected);
+
+        let order = toposort([label(b"CCC"), label(b"AAA")], successors).unwrap();
+        assert_eq!(order, [label(b"AAA"), label(b"BBB"), label(b"CCC")]);
+        let cyclic = |&node: &u32| [(node + 1) % 3];
+        assert_eq!(toposort([0], cyclic), None);
+    }
+
+    #[test]
+    fn longest_path() {
+        // 0 -> 1 -> 3 is longer than 0 -> 3 or 0 -> 2 -> 3
+        let edges = [
+            (0, 1, 2),
+            (0, 2, 1),
+            (0, 3, 4),
+            (1, 3, 5),
+            (2, 3, 1),
+            (3, 4, 1),
+        ];
+        let successors = |&node: &u8| {
+            edges
+                .iter()
+                .filter(move |(from, ..)| *from == node)
+                .map(|&(_, to, cost)| (to, cost))
+        };
+        assert_eq!(
+            dag_longest_path(0, successors, |&node| node == 4),
+            Some((vec![0, 1, 3, 4], 8))
+        );
+        assert_eq!(dag_longest_path(0, successors, |&node| node == 5), None);
+        assert_eq!(
+            dag_longest_path(0, |&node: &u8| [(node ^ 1, 1u8)], |_| true),
+            None
+        );
+    }
+}
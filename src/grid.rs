@@ -0,0 +1,407 @@
+/// Module with a dense 2D grid for character maps, indexed by `(row, col)` from the top left.
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::ops::{Index, IndexMut};
+
+use crate::geometry::{Dir4, Dir8, Direction, Point};
+
+/// A position in a [`Grid`] as `(row, col)`.
+pub type Pos = (usize, usize);
+
+/// An error which can be returned when parsing a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridError {
+    /// A row has a different length than the first one.
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Error for GridError {}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {row} is {found} cells wide, expected {expected} like the first row"
+            ),
+        }
+    }
+}
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Creates a grid from cells stored row by row. Panics if they don't fill whole rows.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            cells.len().is_multiple_of(width),
+            "{} cells do not fill rows of width {width}",
+            cells.len()
+        );
+        let height = cells.len().checked_div(width).unwrap_or(0);
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parses newline separated rows, converting each byte with `cell`.
+    /// Both `\n` and `\r\n` line endings work, and trailing empty lines are ignored.
+    pub fn parse_with(
+        input: &(impl AsRef<[u8]> + ?Sized),
+        mut cell: impl FnMut(u8) -> T,
+    ) -> Result<Self, GridError> {
+        let input = input.as_ref();
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+
+        let mut lines: Vec<_> = input
+            .split(|&c| c == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+
+        for line in lines {
+            let expected = *width.get_or_insert(line.len());
+            if line.len() != expected {
+                return Err(GridError::Ragged {
+                    row: height,
+                    expected,
+                    found: line.len(),
+                });
+            }
+            cells.extend(line.iter().map(|&c| cell(c)));
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// All cells, row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn contains(&self, pos: impl Into<Pos>) -> bool {
+        let (row, col) = pos.into();
+        row < self.height && col < self.width
+    }
+
+    /// Returns the index of `pos` in [`cells`](Self::cells), or [`None`] if it is out of bounds.
+    pub fn index_of(&self, pos: impl Into<Pos>) -> Option<usize> {
+        let pos = pos.into();
+        self.contains(pos).then(|| pos.0 * self.width + pos.1)
+    }
+
+    /// Returns the position of an index in [`cells`](Self::cells).
+    pub fn pos_of(&self, index: usize) -> Pos {
+        (index / self.width, index % self.width)
+    }
+
+    pub fn get(&self, pos: impl Into<Pos>) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: impl Into<Pos>) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Returns `pos` moved by `(rows, cols)`, or [`None`] if that leaves the grid.
+    pub fn offset(&self, pos: impl Into<Pos>, (rows, cols): (isize, isize)) -> Option<Pos> {
+        let (row, col) = pos.into();
+        let pos = (row.checked_add_signed(rows)?, col.checked_add_signed(cols)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Returns `pos` moved one step in `dir`, or [`None`] if that leaves the grid.
+    pub fn step(&self, pos: impl Into<Pos>, dir: impl Direction) -> Option<Pos> {
+        let point = Point::from(pos.into()).checked_step(dir)?;
+        self.contains(point).then(|| point.into())
+    }
+
+    /// The orthogonal neighbors of `pos` inside the grid, clockwise from north.
+    pub fn neighbors4(&self, pos: impl Into<Pos>) -> impl Iterator<Item = Pos> + '_ {
+        let pos = pos.into();
+        Dir4::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// All 8 neighbors of `pos` inside the grid, clockwise from north.
+    pub fn neighbors8(&self, pos: impl Into<Pos>) -> impl Iterator<Item = Pos> + '_ {
+        let pos = pos.into();
+        Dir8::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    /// The cells of a column, top to bottom.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column {col} is out of bounds");
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i / width, i % width))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Returns the first position (row by row) whose cell is `value`.
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.cells
+            .iter()
+            .position(|c| c == value)
+            .map(|i| self.pos_of(i))
+    }
+
+    /// Returns every position whose cell is `value`, row by row.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Pos> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, c)| *c == value)
+            .map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Builds a `width` x `height` grid by taking each cell from `cell(pos)` of this one.
+    fn remap(&self, width: usize, height: usize, cell: impl Fn(Pos) -> Pos) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..width * height)
+            .map(|i| self[cell((i / width, i % width))].clone())
+            .collect();
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, so rows become columns.
+    #[must_use]
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |(row, col)| (col, row))
+    }
+
+    /// Rotates the grid a quarter turn clockwise.
+    #[must_use]
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remap(height, self.width, |(row, col)| (height - 1 - col, row))
+    }
+
+    /// Rotates the grid a quarter turn counter-clockwise.
+    #[must_use]
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remap(self.height, width, |(row, col)| (col, width - 1 - row))
+    }
+}
+
+impl Grid<u8> {
+    /// Parses newline separated rows of bytes, see [`Grid::parse_with`].
+    pub fn parse(input: &(impl AsRef<[u8]> + ?Sized)) -> Result<Self, GridError> {
+        Self::parse_with(input, |c| c)
+    }
+}
+
+impl<T, P: Into<Pos> + Debug + Copy> Index<P> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: P) -> &T {
+        let Some(index) = self.index_of(pos) else {
+            panic!(
+                "{pos:?} is outside of the {}x{} grid",
+                self.width, self.height
+            );
+        };
+        &self.cells[index]
+    }
+}
+
+impl<T, P: Into<Pos> + Debug + Copy> IndexMut<P> for Grid<T> {
+    fn index_mut(&mut self, pos: P) -> &mut T {
+        let Some(index) = self.index_of(pos) else {
+            panic!(
+                "{pos:?} is outside of the {}x{} grid",
+                self.width, self.height
+            );
+        };
+        &mut self.cells[index]
+    }
+}
+
+/// Prints a byte grid the way it was parsed, which is handy for debugging.
+impl Display for Grid<u8> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_line_endings() {
+        let expected = Grid::from_vec(3, b"abcdef".to_vec());
+        assert_eq!(Grid::parse("abc\ndef"), Ok(expected.clone()));
+        assert_eq!(Grid::parse("abc\ndef\n"), Ok(expected.clone()));
+        assert_eq!(Grid::parse("abc\r\ndef\r\n\r\n"), Ok(expected));
+        assert_eq!(Grid::parse("").map(|g| (g.width(), g.height())), Ok((0, 0)));
+        assert_eq!(
+            Grid::parse("abc\nde\n"),
+            Err(GridError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn accesses_by_position() {
+        let mut grid = Grid::parse_with("12\n34", |c| c - b'0').unwrap();
+        assert_eq!(grid[(1, 0)], 3);
+        assert_eq!(grid.get((0, 2)), None);
+        assert_eq!(grid.get((2, 0)), None);
+        *grid.get_mut((0, 1)).unwrap() = 9;
+        grid[(1, 1)] += 1;
+        assert_eq!(grid.cells(), &[1, 9, 3, 5]);
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (1, 1)), Some((1, 1)));
+        assert_eq!(grid.step((0, 0), Dir4::W), None);
+        assert_eq!(grid.step((0, 0), Dir8::SE), Some((1, 1)));
+        assert_eq!(grid[Point::new(1, 0)], 9);
+    }
+
+    #[test]
+    fn neighbors_stay_inside() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbors8((0, 2)).collect::<Vec<_>>(),
+            vec![(1, 2), (1, 1), (0, 1)]
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = Grid::parse("abc\ndef").unwrap();
+        assert_eq!(grid.row(1), b"def");
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), b"be");
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.rows().count(), 2);
+    }
+
+    #[test]
+    fn transposes_and_rotates() {
+        let grid = Grid::parse("abc\ndef").unwrap();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), grid);
+    }
+
+    #[test]
+    fn finds_values() {
+        let grid = Grid::parse("#..\n.#S").unwrap();
+        assert_eq!(grid.find(&b'S'), Some((1, 2)));
+        assert_eq!(grid.find(&b'x'), None);
+        assert_eq!(
+            grid.find_all(&b'#').collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1)]
+        );
+    }
+}
@@ -0,0 +1,414 @@
+/// Module with sets of inclusive integer intervals and piecewise-offset maps between them.
+use crate::math::Int;
+use std::ops::RangeInclusive;
+
+/// Returns whether an interval ending at `end` is strictly before, and not touching, one starting
+/// at `start`.
+fn is_before<T: Int>(end: T, start: T) -> bool {
+    end < start && end + T::ONE < start
+}
+
+/// Returns where `value` ends up when the range starting at `from` is moved to start at `to`.
+/// Wrapping arithmetic keeps this exact even when the distance between two values of a signed
+/// type doesn't fit that type.
+fn shift<T: Int>(value: T, from: T, to: T) -> T {
+    to.wrapping_add(value.wrapping_sub(from))
+}
+
+/// A set of integers stored as sorted, disjoint and non-touching inclusive ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<RangeInclusive<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T: Int> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the set containing every value of `T`.
+    pub fn full() -> Self {
+        Self {
+            ranges: vec![T::MIN..=T::MAX],
+        }
+    }
+
+    /// The ranges in the set, in increasing order.
+    pub fn ranges(&self) -> &[RangeInclusive<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|range| *range.start())
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|range| *range.end())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|range| *range.end() < value);
+        self.ranges
+            .get(i)
+            .is_some_and(|range| range.contains(&value))
+    }
+
+    /// Adds every value in `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let lo = self.ranges.partition_point(|r| is_before(*r.end(), start));
+        let hi = self.ranges.partition_point(|r| !is_before(end, *r.start()));
+        if lo < hi {
+            start = start.min(*self.ranges[lo].start());
+            end = end.max(*self.ranges[hi - 1].end());
+        }
+        self.ranges.splice(lo..hi, [start..=end]);
+    }
+
+    /// Removes every value in `range`, returning the ones that were in the set.
+    #[allow(clippy::return_self_not_must_use)] // it's fine to only want them gone
+    pub fn remove(&mut self, range: RangeInclusive<T>) -> Self {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return Self::new();
+        }
+        let lo = self.ranges.partition_point(|r| *r.end() < start);
+        let hi = self.ranges.partition_point(|r| *r.start() <= end);
+        if lo == hi {
+            return Self::new();
+        }
+        let (first, last) = (*self.ranges[lo].start(), *self.ranges[hi - 1].end());
+        let mut kept = vec![];
+        if first < start {
+            kept.push(first..=start - T::ONE);
+        }
+        if last > end {
+            kept.push(end + T::ONE..=last);
+        }
+        let mut removed: Vec<_> = self.ranges.splice(lo..hi, kept).collect();
+        if let Some(first) = removed.first_mut() {
+            *first = start.max(*first.start())..=*first.end();
+        }
+        if let Some(last) = removed.last_mut() {
+            *last = *last.start()..=end.min(*last.end());
+        }
+        Self { ranges: removed }
+    }
+
+    /// Returns the values of `T` that are not in the set.
+    #[must_use]
+    pub fn complement(&self) -> Self {
+        let mut ranges = vec![];
+        let mut next = Some(T::MIN);
+        for range in &self.ranges {
+            if let Some(next) = next.filter(|next| next < range.start()) {
+                ranges.push(next..=*range.start() - T::ONE);
+            }
+            next = (*range.end() < T::MAX).then(|| *range.end() + T::ONE);
+        }
+        if let Some(next) = next {
+            ranges.push(next..=T::MAX);
+        }
+        Self { ranges }
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let start = *x.start().max(y.start());
+            let end = *x.end().min(y.end());
+            if start <= end {
+                ranges.push(start..=end);
+            }
+            if x.end() < y.end() {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges }
+    }
+
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement())
+    }
+}
+
+impl<T: Int> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut sorted: Vec<_> = iter.into_iter().filter(|range| !range.is_empty()).collect();
+        sorted.sort_unstable_by_key(|range| *range.start());
+        let mut ranges: Vec<RangeInclusive<T>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last) if !is_before(*last.end(), *range.start()) => {
+                    *last = *last.start()..=*last.end().max(range.end());
+                }
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+}
+
+impl<T: Int> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+/// A piece of a [`RangeMap`], moving `source` so it starts at `dest_start`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Piece<T> {
+    source: RangeInclusive<T>,
+    dest_start: T,
+}
+
+/// A function on `T` that moves disjoint source ranges by their own offsets and leaves every
+/// other value where it is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeMap<T> {
+    pieces: Vec<Piece<T>>,
+}
+
+impl<T> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self { pieces: vec![] }
+    }
+}
+
+impl<T: Int> RangeMap<T> {
+    /// Returns the identity map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps `source` onto the range starting at `dest_start`. Returns `false`, leaving the map
+    /// unchanged, if `source` overlaps an existing piece or the destination would pass `T::MAX`.
+    pub fn insert(&mut self, source: RangeInclusive<T>, dest_start: T) -> bool {
+        let (start, end) = (*source.start(), *source.end());
+        if start > end || shift(end, start, dest_start) < dest_start {
+            return false;
+        }
+        let i = self.pieces.partition_point(|p| *p.source.end() < start);
+        if self.pieces.get(i).is_some_and(|p| *p.source.start() <= end) {
+            return false;
+        }
+        if start != dest_start {
+            self.pieces.insert(i, Piece { source, dest_start });
+        }
+        true
+    }
+
+    /// Returns where `value` is mapped to.
+    pub fn get(&self, value: T) -> T {
+        let i = self.pieces.partition_point(|p| *p.source.end() < value);
+        match self.pieces.get(i) {
+            Some(p) if p.source.contains(&value) => shift(value, *p.source.start(), p.dest_start),
+            _ => value,
+        }
+    }
+
+    /// Splits `range` by the pieces it overlaps, returning each part with the range it maps to.
+    fn split(&self, range: &RangeInclusive<T>) -> Vec<(RangeInclusive<T>, RangeInclusive<T>)> {
+        let (mut cursor, end) = (*range.start(), *range.end());
+        let mut parts = vec![];
+        if cursor > end {
+            return parts;
+        }
+        let i = self.pieces.partition_point(|p| *p.source.end() < cursor);
+        for p in self.pieces[i..]
+            .iter()
+            .take_while(|p| *p.source.start() <= end)
+        {
+            if cursor < *p.source.start() {
+                let gap = cursor..=*p.source.start() - T::ONE;
+                parts.push((gap.clone(), gap));
+            }
+            let start = cursor.max(*p.source.start());
+            let part_end = end.min(*p.source.end());
+            let dest = |v| shift(v, *p.source.start(), p.dest_start);
+            parts.push((start..=part_end, dest(start)..=dest(part_end)));
+            if part_end == end {
+                return parts;
+            }
+            cursor = part_end + T::ONE;
+        }
+        parts.push((cursor..=end, cursor..=end));
+        parts
+    }
+
+    /// Returns the image of `set` under the map.
+    pub fn apply(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.ranges
+            .iter()
+            .flat_map(|range| self.split(range))
+            .map(|(_, dest)| dest)
+            .collect()
+    }
+
+    /// Returns the map that applies `self` and then `next`.
+    #[must_use]
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = vec![];
+        for (source, dest) in self.split(&(T::MIN..=T::MAX)) {
+            for (next_source, next_dest) in next.split(&dest) {
+                let start = shift(*next_source.start(), *dest.start(), *source.start());
+                if start != *next_dest.start() {
+                    let end = shift(*next_source.end(), *dest.start(), *source.start());
+                    pieces.push(Piece {
+                        source: start..=end,
+                        dest_start: *next_dest.start(),
+                    });
+                }
+            }
+        }
+        pieces.sort_unstable_by_key(|p| *p.source.start());
+        Self { pieces }
+    }
+
+    /// Returns the inverse map, or [`None`] if some values are mapped onto the same value so the
+    /// map has no inverse.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut pieces: Vec<_> = self
+            .pieces
+            .iter()
+            .map(|p| Piece {
+                source: p.dest_start..=shift(*p.source.end(), *p.source.start(), p.dest_start),
+                dest_start: *p.source.start(),
+            })
+            .collect();
+        pieces.sort_unstable_by_key(|p| *p.source.start());
+        // the destinations can't overlap, and they have to cover exactly the values that were
+        // moved out of the way or two values would end up in the same place.
+        let disjoint = pieces
+            .windows(2)
+            .all(|w| w[0].source.end() < w[1].source.start());
+        let moved: IntervalSet<T> = self.pieces.iter().map(|p| p.source.clone()).collect();
+        let landed: IntervalSet<T> = pieces.iter().map(|p| p.source.clone()).collect();
+        (disjoint && moved == landed).then_some(Self { pieces })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn set<T: Int>(ranges: impl IntoIterator<Item = RangeInclusive<T>>) -> IntervalSet<T> {
+        ranges.into_iter().collect()
+    }
+
+    #[test]
+    fn insert_merges() {
+        let mut s = IntervalSet::new();
+        s.insert(10u8..=20);
+        s.insert(30..=40);
+        s.insert(21..=22);
+        assert_eq!(s.ranges(), [10..=22, 30..=40]);
+        s.insert(0..=9);
+        s.insert(25..=35);
+        assert_eq!(s.ranges(), [0..=22, 25..=40]);
+        s.insert(23..=24);
+        assert_eq!(s.ranges(), [0..=40]);
+        s.insert(250..=255);
+        s.insert(5..=1);
+        assert_eq!(s.ranges(), [0..=40, 250..=255]);
+        assert_eq!(s, set([250..=255, 0..=20, 21..=40, 3..=4]));
+        assert!(s.contains(255) && s.contains(0) && !s.contains(41));
+        assert_eq!((s.min(), s.max()), (Some(0), Some(255)));
+    }
+
+    #[test]
+    fn remove_returns_removed() {
+        let mut s = set([0u8..=10, 20..=30, 250..=255]);
+        assert_eq!(s.remove(5..=25), set([5..=10, 20..=25]));
+        assert_eq!(s, set([0..=4, 26..=30, 250..=255]));
+        assert_eq!(s.remove(11..=25), IntervalSet::new());
+        assert_eq!(s.remove(252..=255), set([252..=255]));
+        assert_eq!(s.remove(0..=255), set([0..=4, 26..=30, 250..=251]));
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set([0u8..=10, 20..=30]);
+        let b = set([5..=25, 255..=255]);
+        assert_eq!(a.union(&b), set([0..=30, 255..=255]));
+        assert_eq!(a.intersection(&b), set([5..=10, 20..=25]));
+        assert_eq!(a.difference(&b), set([0..=4, 26..=30]));
+        assert_eq!(b.complement(), set([0..=4, 26..=254]));
+        assert_eq!(IntervalSet::<u8>::new().complement(), IntervalSet::full());
+        assert_eq!(IntervalSet::<i8>::full().complement(), IntervalSet::new());
+        assert_eq!(set([-128i8..=-1]).complement(), set([0..=127]));
+    }
+
+    #[test]
+    fn range_map() {
+        let mut m = RangeMap::new();
+        assert!(m.insert(98u8..=99, 50));
+        assert!(m.insert(50..=97, 52));
+        assert!(!m.insert(90..=100, 0));
+        assert!(!m.insert(200..=210, 250));
+        assert!(m.insert(250..=255, 0));
+        assert_eq!(
+            [0, 49, 50, 97, 98, 99, 100, 255].map(|v| m.get(v)),
+            [0, 49, 52, 99, 50, 51, 100, 5]
+        );
+        assert_eq!(
+            m.apply(&set([40..=60, 99..=255])),
+            set([0..=5, 40..=49, 51..=62, 100..=249])
+        );
+        assert_eq!(m.apply(&IntervalSet::full()), set([0..=249]));
+
+        let mut shift = RangeMap::new();
+        assert!(shift.insert(0..=9, 246));
+        assert!(shift.insert(246..=255, 0));
+        let both = m.then(&shift);
+        for v in 0..=255 {
+            assert_eq!(both.get(v), shift.get(m.get(v)), "{v}");
+        }
+    }
+
+    #[test]
+    fn range_map_inverse() {
+        let mut m = RangeMap::new();
+        assert!(m.insert(0i8..=9, 100));
+        assert!(m.insert(100..=127, 0));
+        assert_eq!(m.inverse(), None);
+
+        let mut swap = RangeMap::new();
+        assert!(swap.insert(-128i8..=-1, 0));
+        assert!(swap.insert(0..=127, -128));
+        let inverse = swap.inverse().unwrap();
+        for v in -128..=127 {
+            assert_eq!(inverse.get(swap.get(v)), v);
+        }
+        assert_eq!(swap.then(&inverse), RangeMap::new());
+    }
+}
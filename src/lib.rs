@@ -1,4 +1,24 @@
+pub mod bits;
+pub mod collections;
+pub mod cycle;
 mod day;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod matcher;
+pub mod math;
+pub mod memo;
+pub mod parallel;
+pub mod parse;
+pub mod polygon;
+pub mod rational;
+pub mod scan;
+pub mod sequence;
+pub mod simulation;
+pub mod stream;
+pub mod swar;
 pub mod template;
+pub mod union_find;
 
 pub use day::*;
@@ -1,14 +1,25 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
+use advent_of_code::template::commands::{
+    all, download, fuzz, read, scaffold, scale, solve, today,
+};
 use args::{parse, AppArguments};
 
 mod args {
     use std::process;
 
+    use advent_of_code::template::commands::solve::Stream;
     use advent_of_code::Day;
 
     pub enum AppArguments {
         Download {
             day: Day,
+            wait: bool,
+        },
+        Fuzz {
+            day: Day,
+            release: bool,
+            part: Option<u8>,
+            runs: Option<u64>,
+            seed: Option<u64>,
         },
         Read {
             day: Day,
@@ -16,16 +27,26 @@ mod args {
         Scaffold {
             day: Day,
         },
+        Scale {
+            day: Day,
+            part: Option<u8>,
+            seed: Option<u64>,
+        },
         Solve {
             day: Day,
             release: bool,
             time: bool,
             submit: Option<u8>,
+            variants: bool,
+            threads: Option<usize>,
+            stream: Option<Stream>,
         },
         All {
             release: bool,
             time: bool,
+            threads: Option<usize>,
         },
+        Today,
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
@@ -35,9 +56,18 @@ mod args {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
                 time: args.contains("--time"),
+                threads: args.opt_value_from_str("--threads")?,
             },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
+                wait: args.contains("--wait"),
+            },
+            Some("mutate") => AppArguments::Fuzz {
+                day: args.free_from_str()?,
+                release: args.contains("--release"),
+                part: args.opt_value_from_str("--part")?,
+                runs: args.opt_value_from_str("--runs")?,
+                seed: args.opt_value_from_str("--seed")?,
             },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
@@ -45,12 +75,29 @@ mod args {
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
             },
-            Some("solve") => AppArguments::Solve {
+            Some("scale") => AppArguments::Scale {
                 day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                time: args.contains("--time"),
+                part: args.opt_value_from_str("--part")?,
+                seed: args.opt_value_from_str("--seed")?,
             },
+            Some("solve") => {
+                let part = args.opt_value_from_str("--part")?;
+                let stream: Option<String> = args.opt_value_from_str("--stream")?;
+                if part.is_some() && stream.is_none() {
+                    eprintln!("--part only works with --stream.");
+                    process::exit(1);
+                }
+                AppArguments::Solve {
+                    day: args.free_from_str()?,
+                    release: args.contains("--release"),
+                    submit: args.opt_value_from_str("--submit")?,
+                    time: args.contains("--time"),
+                    variants: args.contains("--variants"),
+                    threads: args.opt_value_from_str("--threads")?,
+                    stream: stream.map(|path| Stream { path, part }),
+                }
+            }
+            Some("today") => AppArguments::Today,
             Some(x) => {
                 eprintln!("Unknown command: {x}");
                 process::exit(1);
@@ -77,16 +124,32 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
-            AppArguments::Download { day } => download::handle(day),
+            AppArguments::All {
+                release,
+                time,
+                threads,
+            } => all::handle(release, time, threads),
+            AppArguments::Download { day, wait } => download::handle(day, wait),
+            AppArguments::Fuzz {
+                day,
+                release,
+                part,
+                runs,
+                seed,
+            } => fuzz::handle(day, release, part, runs, seed),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold { day } => scaffold::handle(day),
+            AppArguments::Scale { day, part, seed } => scale::handle(day, part, seed),
             AppArguments::Solve {
                 day,
                 release,
                 time,
                 submit,
-            } => solve::handle(day, release, time, submit),
+                variants,
+                threads,
+                stream,
+            } => solve::handle(day, release, time, submit, variants, threads, stream),
+            AppArguments::Today => today::handle(),
         },
     };
 }
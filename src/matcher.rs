@@ -0,0 +1,341 @@
+/// Module that finds many byte patterns at once, overlapping matches included, with an
+/// Aho-Corasick automaton.
+use std::collections::VecDeque;
+
+/// Marks a missing transition or output.
+const NONE: u32 = u32::MAX;
+const ROOT: u32 = 0;
+
+/// A set of patterns, each with a value, compiled into a DFA that reads a haystack one byte at
+/// a time.
+#[derive(Debug, Clone)]
+pub struct Matcher<V> {
+    /// Maps each byte to its column in `transitions`. Bytes that aren't in any pattern share
+    /// column `0`, so there can be up to 257 columns.
+    classes: [u16; 256],
+    columns: usize,
+    /// The next state for each state and byte class.
+    transitions: Vec<u32>,
+    /// The longest pattern that ends at each state, if any.
+    outputs: Vec<u32>,
+    /// The nearest state down the failure links that has an output, if any.
+    output_links: Vec<u32>,
+    patterns: Vec<(usize, V)>,
+    longest: usize,
+}
+
+/// A pattern found in a haystack.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Match<'m, V> {
+    /// The index of the pattern, in the order they were given.
+    pub pattern: usize,
+    pub start: usize,
+    /// One past the last byte, so the match is `haystack[start..end]`.
+    pub end: usize,
+    pub value: &'m V,
+}
+
+impl<V> Clone for Match<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Match<'_, V> {}
+
+impl<V> Matcher<V> {
+    /// Builds the automaton. Empty patterns are ignored, and when a pattern repeats only its
+    /// first value is reported.
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = (P, V)>) -> Self {
+        let patterns: Vec<(P, V)> = patterns.into_iter().collect();
+
+        let mut classes = [0u16; 256];
+        let mut columns = 1;
+        for (pattern, _) in &patterns {
+            for &byte in pattern.as_ref() {
+                if classes[usize::from(byte)] == 0 {
+                    classes[usize::from(byte)] = columns;
+                    columns += 1;
+                }
+            }
+        }
+        let columns = usize::from(columns);
+        let class = |byte: u8| usize::from(classes[usize::from(byte)]);
+
+        // the trie of the patterns
+        let mut transitions = vec![NONE; columns];
+        let mut outputs = vec![NONE];
+        for (index, (pattern, _)) in (0..).zip(&patterns) {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                continue;
+            }
+            let mut state = ROOT as usize;
+            for &byte in pattern {
+                let next = &mut transitions[state * columns + class(byte)];
+                if *next == NONE {
+                    *next = u32::try_from(outputs.len()).expect("too many states");
+                    transitions.extend(std::iter::repeat_n(NONE, columns));
+                    outputs.push(NONE);
+                }
+                state = transitions[state * columns + class(byte)] as usize;
+            }
+            if outputs[state] == NONE {
+                outputs[state] = index;
+            }
+        }
+
+        // fill in the missing transitions breadth first, so each state's failure state is
+        // complete before the state itself.
+        let mut failures = vec![ROOT; outputs.len()];
+        let mut output_links = vec![NONE; outputs.len()];
+        let mut queue = VecDeque::new();
+        for next in &mut transitions[..columns] {
+            if *next == NONE {
+                *next = ROOT;
+            } else {
+                queue.push_back(*next);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            let state = state as usize;
+            let failure = failures[state] as usize;
+            output_links[state] = if outputs[failure] == NONE {
+                output_links[failure]
+            } else {
+                failure as u32
+            };
+            for column in 0..columns {
+                let fallback = transitions[failure * columns + column];
+                let next = &mut transitions[state * columns + column];
+                if *next == NONE {
+                    *next = fallback;
+                } else {
+                    failures[*next as usize] = fallback;
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        let patterns: Vec<(usize, V)> = patterns
+            .into_iter()
+            .map(|(pattern, value)| (pattern.as_ref().len(), value))
+            .collect();
+        let longest = patterns.iter().map(|&(len, _)| len).max().unwrap_or(0);
+        Self {
+            classes,
+            columns,
+            transitions,
+            outputs,
+            output_links,
+            patterns,
+            longest,
+        }
+    }
+
+    /// The number of patterns, including empty and repeated ones.
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns the value of the pattern with index `pattern`.
+    pub fn value(&self, pattern: usize) -> Option<&V> {
+        self.patterns.get(pattern).map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn step(&self, state: u32, byte: u8) -> u32 {
+        let column = usize::from(self.classes[usize::from(byte)]);
+        self.transitions[state as usize * self.columns + column]
+    }
+
+    /// Returns the first output at `state`, which is either its own or the nearest one down the
+    /// failure links.
+    #[inline]
+    fn first_output(&self, state: u32) -> u32 {
+        if self.outputs[state as usize] == NONE {
+            self.output_links[state as usize]
+        } else {
+            state
+        }
+    }
+
+    fn report(&self, state: u32, end: usize) -> Match<'_, V> {
+        let pattern = self.outputs[state as usize] as usize;
+        let (len, value) = &self.patterns[pattern];
+        Match {
+            pattern,
+            start: end - len,
+            end,
+            value,
+        }
+    }
+
+    /// Returns every match in `haystack`, overlapping ones included, ordered by where they end.
+    /// Matches that end at the same place come longest first.
+    pub fn find_iter<'m, 'h>(
+        &'m self,
+        haystack: &'h (impl AsRef<[u8]> + ?Sized),
+    ) -> Matches<'m, 'h, V> {
+        Matches {
+            matcher: self,
+            haystack: haystack.as_ref(),
+            pos: 0,
+            state: ROOT,
+            pending: NONE,
+        }
+    }
+
+    /// Returns whether any pattern appears in `haystack`.
+    pub fn is_match(&self, haystack: &(impl AsRef<[u8]> + ?Sized)) -> bool {
+        self.find_iter(haystack).next().is_some()
+    }
+
+    /// Returns the match that starts first; of those that start at the same place, the shortest.
+    ///
+    /// Stops reading as soon as no later match could start earlier.
+    pub fn first(&self, haystack: &(impl AsRef<[u8]> + ?Sized)) -> Option<Match<'_, V>> {
+        let mut best: Option<Match<'_, V>> = None;
+        let mut state = ROOT;
+        for (end, &byte) in (1..).zip(haystack.as_ref()) {
+            if best.is_some_and(|best| end > best.start + self.longest) {
+                break;
+            }
+            state = self.step(state, byte);
+            let mut output = self.first_output(state);
+            while output != NONE {
+                let found = self.report(output, end);
+                if best.is_none_or(|best| found.start < best.start) {
+                    best = Some(found);
+                }
+                output = self.output_links[output as usize];
+            }
+        }
+        best
+    }
+
+    /// Returns the match that ends last; of those that end at the same place, the shortest. This
+    /// mirrors [`first`](Self::first).
+    pub fn last(&self, haystack: &(impl AsRef<[u8]> + ?Sized)) -> Option<Match<'_, V>> {
+        let mut last = None;
+        let mut state = ROOT;
+        for (end, &byte) in (1..).zip(haystack.as_ref()) {
+            state = self.step(state, byte);
+            let mut output = self.first_output(state);
+            // the shortest match ending here is the last one down the links
+            while output != NONE {
+                last = Some((output, end));
+                output = self.output_links[output as usize];
+            }
+        }
+        last.map(|(state, end)| self.report(state, end))
+    }
+}
+
+/// The iterator returned by [`Matcher::find_iter`].
+#[derive(Debug, Clone)]
+pub struct Matches<'m, 'h, V> {
+    matcher: &'m Matcher<V>,
+    haystack: &'h [u8],
+    pos: usize,
+    state: u32,
+    /// The next state whose output is still to be reported at `pos`.
+    pending: u32,
+}
+
+impl<'m, V> Iterator for Matches<'m, '_, V> {
+    type Item = Match<'m, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending == NONE {
+            let &byte = self.haystack.get(self.pos)?;
+            self.pos += 1;
+            self.state = self.matcher.step(self.state, byte);
+            self.pending = self.matcher.first_output(self.state);
+        }
+        let found = self.matcher.report(self.pending, self.pos);
+        self.pending = self.matcher.output_links[self.pending as usize];
+        Some(found)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn spans<V>(matcher: &Matcher<V>, haystack: &str) -> Vec<(usize, usize, usize)> {
+        matcher
+            .find_iter(haystack)
+            .map(|m| (m.pattern, m.start, m.end))
+            .collect()
+    }
+
+    /// Every occurrence of every pattern, found the slow way and sorted like `find_iter`.
+    fn naive(patterns: &[&str], haystack: &str) -> Vec<(usize, usize, usize)> {
+        let mut found = vec![];
+        for (index, pattern) in patterns.iter().enumerate() {
+            if pattern.is_empty() || patterns[..index].contains(pattern) {
+                continue;
+            }
+            for start in 0..=haystack.len().saturating_sub(pattern.len()) {
+                if haystack[start..].starts_with(pattern) {
+                    found.push((index, start, start + pattern.len()));
+                }
+            }
+        }
+        found.sort_by_key(|&(_, start, end)| (end, start));
+        found
+    }
+
+    #[test]
+    fn overlapping_matches() {
+        let patterns = ["he", "she", "his", "hers"];
+        let matcher = Matcher::new(patterns.iter().map(|p| (p, ())));
+        assert_eq!(spans(&matcher, "ushers"), [(1, 1, 4), (0, 2, 4), (3, 2, 6)]);
+        assert_eq!(spans(&matcher, "ushers"), naive(&patterns, "ushers"));
+        assert!(!matcher.is_match("hi you"));
+        assert!(matcher.is_match("hi there"));
+
+        // patterns that repeat their first letter, and patterns inside other patterns
+        let patterns = ["nine", "ni", "aa", "aaa", "", "a", "nine", "inin"];
+        let matcher = Matcher::new(patterns.iter().map(|p| (p, ())));
+        for haystack in ["ninine", "aaaaa", "nininineaa", "", "xyz", "ninini"] {
+            assert_eq!(
+                spans(&matcher, haystack),
+                naive(&patterns, haystack),
+                "{haystack}"
+            );
+        }
+    }
+
+    #[test]
+    fn first_and_last() {
+        let matcher = Matcher::new([("abcd", 'a'), ("bc", 'b'), ("cde", 'c'), ("d", 'd')]);
+        let first = matcher.first("xabcde").unwrap();
+        assert_eq!((first.value, first.start, first.end), (&'a', 1, 5));
+        let last = matcher.last("xabcde").unwrap();
+        assert_eq!((last.value, last.start, last.end), (&'c', 3, 6));
+        let last = matcher.last("xabcd").unwrap();
+        assert_eq!((last.value, last.start), (&'d', 4));
+        assert_eq!(matcher.first("xyz"), None);
+        assert_eq!(matcher.last(""), None);
+        assert_eq!(matcher.value(2), Some(&'c'));
+
+        // patterns that use every byte between them
+        let bytes: Vec<[u8; 2]> = (0..=255).map(|b: u8| [b, b.wrapping_add(1)]).collect();
+        let matcher = Matcher::new(bytes.iter().zip(0..));
+        let found: Vec<_> = matcher
+            .find_iter(&[254, 255, 0])
+            .map(|m| *m.value)
+            .collect();
+        assert_eq!(found, [254, 255]);
+
+        let empty: Matcher<()> = Matcher::new::<&str>([]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.first("abc"), None);
+    }
+}
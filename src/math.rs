@@ -0,0 +1,441 @@
+/// Module with number theory helpers: gcd and lcm, primes, modular arithmetic and the Chinese
+/// remainder theorem.
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// An integer type the gcd and lcm helpers work with, also used for [`interval`](crate::interval)
+/// endpoints.
+pub trait Int:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    /// Returns the absolute value; unsigned types return themselves.
+    #[must_use]
+    fn abs(self) -> Self;
+    #[must_use]
+    fn wrapping_add(self, other: Self) -> Self;
+    #[must_use]
+    fn wrapping_sub(self, other: Self) -> Self;
+    /// Returns the integer square root, or [`None`] if `self` is negative.
+    fn checked_isqrt(self) -> Option<Self>;
+}
+
+/// A signed [`Int`], needed where Bézout coefficients can be negative.
+pub trait SignedInt: Int + Neg<Output = Self> {}
+
+macro_rules! impl_int {
+    (unsigned: $($t:ty),*) => {$(
+        impl Int for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            #[inline]
+            fn wrapping_add(self, other: Self) -> Self {
+                <$t>::wrapping_add(self, other)
+            }
+
+            #[inline]
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$t>::wrapping_sub(self, other)
+            }
+
+            #[inline]
+            fn abs(self) -> Self {
+                self
+            }
+
+            #[inline]
+            fn checked_isqrt(self) -> Option<Self> {
+                Some(self.isqrt())
+            }
+        }
+    )*};
+    (signed: $($t:ty),*) => {$(
+        impl Int for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            #[inline]
+            fn wrapping_add(self, other: Self) -> Self {
+                <$t>::wrapping_add(self, other)
+            }
+
+            #[inline]
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$t>::wrapping_sub(self, other)
+            }
+
+            #[inline]
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            #[inline]
+            fn checked_isqrt(self) -> Option<Self> {
+                <$t>::checked_isqrt(self)
+            }
+        }
+
+        impl SignedInt for $t {}
+    )*};
+}
+
+impl_int!(unsigned: u8, u16, u32, u64, u128, usize);
+impl_int!(signed: i8, i16, i32, i64, i128, isize);
+
+/// Returns the greatest common divisor of `a` and `b`, which is never negative.
+/// `gcd(0, 0)` is `0`.
+pub fn gcd<T: Int>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+/// Returns the least common multiple of `a` and `b`, or `0` if either is `0`.
+///
+/// # Panics
+///
+/// Panics in debug builds if the result overflows `T`.
+pub fn lcm<T: Int>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g`, where `g` is `gcd(a, b)`.
+pub fn ext_gcd<T: SignedInt>(a: T, b: T) -> (T, T, T) {
+    let (mut rem, mut next_rem) = (a, b);
+    let (mut x, mut next_x) = (T::ONE, T::ZERO);
+    let (mut y, mut next_y) = (T::ZERO, T::ONE);
+    while next_rem != T::ZERO {
+        let quotient = rem / next_rem;
+        (rem, next_rem) = (next_rem, rem - quotient * next_rem);
+        (x, next_x) = (next_x, x - quotient * next_x);
+        (y, next_y) = (next_y, y - quotient * next_y);
+    }
+    if rem < T::ZERO {
+        (-rem, -x, -y)
+    } else {
+        (rem, x, y)
+    }
+}
+
+/// Returns `a` reduced into `0..m`, also for negative `a`.
+fn rem_euclid<T: Int>(a: T, m: T) -> T {
+    let r = a % m;
+    if r < T::ZERO {
+        r + m.abs()
+    } else {
+        r
+    }
+}
+
+/// Returns the largest integer whose square is at most `n`, or [`None`] if `n` is negative.
+pub fn isqrt<T: Int>(n: T) -> Option<T> {
+    n.checked_isqrt()
+}
+
+/// Returns whether `n` is the square of an integer.
+pub fn is_square<T: Int>(n: T) -> bool {
+    isqrt(n).is_some_and(|root| root * root == n)
+}
+
+/// Returns `x` in `0..m` with `a * x ≡ 1 (mod m)`, or [`None`] if `m` isn't positive or `a`
+/// and `m` are not coprime.
+pub fn mod_inverse<T: SignedInt>(a: T, m: T) -> Option<T> {
+    if m <= T::ZERO {
+        return None;
+    }
+    let (g, x, _) = ext_gcd(rem_euclid(a, m), m);
+    (g == T::ONE).then(|| rem_euclid(x, m))
+}
+
+/// Returns `base.pow(exp) % m`, without overflowing for any `u64` arguments.
+///
+/// # Panics
+///
+/// Panics if `m` is `0`.
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    let m = u128::from(m);
+    let mut base = u128::from(base) % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    u64::try_from(result).unwrap()
+}
+
+/// Solves a system of congruences `x ≡ r (mod m)` given as `(r, m)` pairs.
+///
+/// The moduli don't need to be coprime. Returns the smallest non-negative solution and the
+/// combined modulus (the lcm of all moduli), or [`None`] if a modulus isn't positive, the
+/// congruences contradict each other or the combined modulus doesn't fit an `i64`. An empty
+/// system gives `(0, 1)`.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    let mut congruences = congruences.into_iter();
+    congruences.try_fold((0, 1), |(r1, m1), (r2, m2)| {
+        if m2 <= 0 {
+            return None;
+        }
+        let (r1, m1, r2, m2) = (
+            i128::from(r1),
+            i128::from(m1),
+            i128::from(r2),
+            i128::from(m2),
+        );
+        let (g, inverse, _) = ext_gcd(m1, m2);
+        let diff = r2 - r1;
+        if diff % g != 0 {
+            return None;
+        }
+        let m = m1 / g * m2;
+        // m1 * inverse ≡ g (mod m2), so taking diff / g * inverse steps of m1 from r1 lands
+        // on r2 (mod m2).
+        let steps = rem_euclid(diff / g % (m2 / g) * inverse, m2 / g);
+        let r = rem_euclid(r1 + m1 * steps, m);
+        Some((i64::try_from(r).ok()?, i64::try_from(m).ok()?))
+    })
+}
+
+/// Returns every prime below `limit`, using the sieve of Eratosthenes.
+pub fn sieve(limit: usize) -> Vec<usize> {
+    let mut composite = vec![false; limit];
+    let mut primes = vec![];
+    for n in 2..limit {
+        if composite[n] {
+            continue;
+        }
+        primes.push(n);
+        for multiple in (n * n..limit).step_by(n) {
+            composite[multiple] = true;
+        }
+    }
+    primes
+}
+
+const COUNTER_PRIMES: [u8; 8] = [5, 7, 11, 13, 17, 19, 23, 29];
+const CHECK_STARTING_INDEX: usize = 2 + COUNTER_PRIMES.len();
+
+/// An unbounded iterator over the primes, for when there is no limit to sieve up to.
+// adapted from https://zsmith.co/primes.php
+#[derive(Debug)]
+pub struct Primes {
+    found: Vec<u32>,
+    index: usize,
+    counters: [u8; 8],
+    check_to_index: usize,
+    check_safe_until: u32,
+    skip_four: bool,
+}
+
+impl Primes {
+    pub fn new() -> Self {
+        Self {
+            found: vec![
+                2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79,
+                83, 89, 97,
+            ],
+            index: 0,
+
+            // these are based on starting at 97 (6*16 + 1)
+            counters: [2, 6, 9, 6, 12, 2, 5, 10],
+            // (we check (6*16 + 5) next
+            skip_four: true,
+
+            // this starts after the primes we keep counters for
+            check_to_index: CHECK_STARTING_INDEX,
+            check_safe_until: 841,
+        }
+    }
+}
+
+impl Default for Primes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index;
+        if index < self.found.len() {
+            self.index += 1;
+            return Some(self.found[index]);
+        }
+
+        let mut maybe_prime = self.found[index - 1];
+        loop {
+            // all primes are (6n + 1) or (6n + 5), so alternate +2 and +4
+            let inc: u8 = 2 + 2 * u8::from(self.skip_four);
+            self.skip_four = !self.skip_four;
+
+            maybe_prime = maybe_prime.checked_add(u32::from(inc))?;
+
+            let mut is_composite = false;
+            for (p, modulus) in COUNTER_PRIMES.iter().zip(self.counters.iter_mut()) {
+                *modulus += inc;
+                if *modulus >= *p {
+                    *modulus -= p;
+                    is_composite = is_composite || *modulus == 0;
+                }
+            }
+
+            if is_composite {
+                continue;
+            }
+
+            if maybe_prime > self.check_safe_until {
+                self.check_to_index += 1;
+                let highest_check_prime = self.found[self.check_to_index];
+                self.check_safe_until = highest_check_prime.saturating_mul(highest_check_prime);
+            }
+
+            for p in &self.found[CHECK_STARTING_INDEX..=self.check_to_index] {
+                is_composite = is_composite || maybe_prime.is_multiple_of(*p);
+            }
+
+            if is_composite {
+                continue;
+            }
+
+            self.found.push(maybe_prime);
+            self.index += 1;
+            break Some(maybe_prime);
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(2u32, 4), 2);
+        assert_eq!(gcd(1u32, 5), 1);
+        assert_eq!(gcd(6u32, 8), 2);
+
+        // the following tests came from:
+        // https://codereview.stackexchange.com/questions/183211/find-the-greatest-common-divisor-with-unit-tests
+        assert_eq!(gcd(42u32, 56), 14);
+        assert_eq!(gcd(461_952u32, 116_298), 18);
+        assert_eq!(gcd(7_966_496u32, 314_080_416), 32);
+        assert_eq!(gcd(24_826_148u32, 45_296_490), 526);
+        assert_eq!(gcd(12u32, 0), 12);
+        assert_eq!(gcd(0u32, 0), 0);
+        assert_eq!(gcd(0u32, 9), 9);
+
+        assert_eq!(gcd(-4i32, 6), 2);
+        assert_eq!(gcd(4i32, -6), 2);
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(-4i64, 6), 12);
+        assert_eq!(lcm(0u8, 6), 0);
+        assert_eq!([2u64, 3, 4, 5].into_iter().fold(1, lcm), 60);
+    }
+
+    #[test]
+    fn ext_gcd_bezout() {
+        for (a, b) in [
+            (240i64, 46),
+            (46, 240),
+            (-240, 46),
+            (240, -46),
+            (7, 0),
+            (0, 7),
+            (0, 0),
+        ] {
+            let (g, x, y) = ext_gcd(a, b);
+            assert_eq!(g, gcd(a, b), "{a} {b}");
+            assert_eq!(a * x + b * y, g, "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn square_roots() {
+        assert_eq!(isqrt(0u8), Some(0));
+        assert_eq!(isqrt(15u32), Some(3));
+        assert_eq!(isqrt(16i64), Some(4));
+        assert_eq!(isqrt(-1i64), None);
+        assert_eq!(isqrt(u128::MAX), Some(u128::from(u64::MAX)));
+        assert!(is_square(u64::from(u32::MAX).pow(2)));
+        assert!(!is_square(u64::from(u32::MAX).pow(2) - 1));
+        assert!(!is_square(-4i32));
+    }
+
+    #[test]
+    fn modular() {
+        assert_eq!(mod_inverse(3i64, 7), Some(5));
+        assert_eq!(mod_inverse(-3i64, 7), Some(2));
+        assert_eq!(mod_inverse(4i64, 8), None);
+        assert_eq!(mod_inverse(3i64, 0), None);
+        assert_eq!(mod_inverse(3i64, -7), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt([]), Some((0, 1)));
+        // aligned cycles are solved by their lcm
+        assert_eq!(
+            crt([(0, 12_643), (0, 14_257)]),
+            Some((0, 12_643 * 14_257 / gcd(12_643, 14_257)))
+        );
+        assert_eq!(crt([(0, i64::MAX), (0, i64::MAX - 1)]), None);
+        assert_eq!(crt([(2, 3), (1, 0)]), None);
+        assert_eq!(crt([(1, -4)]), None);
+    }
+
+    #[test]
+    fn primes() {
+        let mut it = Primes::new();
+        assert_eq!(it.next(), Some(2));
+        assert_eq!(it.next(), Some(3));
+        let mut it = it.skip(22);
+        assert_eq!(it.next(), Some(97));
+        assert_eq!(it.next(), Some(101));
+        assert_eq!(it.next(), Some(103));
+        let mut it = it.skip(22);
+        assert_eq!(it.next(), Some(229));
+        let mut it = it.skip(1099);
+        assert_eq!(it.next(), Some(9283));
+        let mut it = it.skip(849);
+        assert_eq!(it.next(), Some(17389));
+
+        let sieved = sieve(17_390);
+        assert_eq!(sieved.len(), 2000);
+        assert!(sieved
+            .iter()
+            .zip(Primes::new())
+            .all(|(&a, b)| a == b as usize));
+        assert_eq!(sieve(2), []);
+    }
+}
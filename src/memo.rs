@@ -0,0 +1,223 @@
+/// Module with a cache for recursive functions, keyed by their arguments.
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+use crate::collections::FastHashMap;
+
+/// How well a [`Memo`] has been doing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Lookups that found a value.
+    pub hits: u64,
+    /// Lookups that didn't, so the value had to be computed.
+    pub misses: u64,
+    /// Values dropped to stay within the capacity.
+    pub evictions: u64,
+}
+
+/// Remembers the values computed for each key.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    values: FastHashMap<K, V>,
+    /// The keys in the order they were inserted, only kept when there is a capacity.
+    order: VecDeque<K>,
+    capacity: Option<usize>,
+    stats: Stats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            values: FastHashMap::default(),
+            order: VecDeque::new(),
+            capacity: None,
+            stats: Stats::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    /// Creates a cache that keeps every value.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cache that keeps at most `capacity` values, dropping the oldest first.
+    pub fn bounded(capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the value stored for `key`, counting a hit or a miss.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.values.get(key).cloned();
+        if value.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        value
+    }
+
+    /// Stores `value` for `key`, dropping the oldest value if the cache is full.
+    pub fn insert(&mut self, key: K, value: V) {
+        let Some(capacity) = self.capacity else {
+            self.values.insert(key, value);
+            return;
+        };
+        if capacity == 0 {
+            return;
+        }
+        if self.values.insert(key.clone(), value).is_none() {
+            self.order.push_back(key);
+        }
+        while self.values.len() > capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.values.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+    }
+
+    /// Returns `f(recurse, key)`, or the value stored from an earlier call with the same `key`.
+    /// `f` should call `recurse` instead of itself, so those calls use the cache too.
+    pub fn call<F>(&mut self, key: K, f: &F) -> V
+    where
+        F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+    {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = f(&mut |key| self.call(key, f), key.clone());
+        self.insert(key, value.clone());
+        value
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// The number of values stored.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Drops every value and resets the statistics.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.order.clear();
+        self.stats = Stats::default();
+    }
+}
+
+/// Wraps the recursive function `f` (see [`Memo::call`]) into a closure with its own cache.
+pub fn memoize<K, V, F>(f: F) -> impl FnMut(K) -> V
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    let mut memo = Memo::new();
+    move |key| memo.call(key, &f)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    /// Counts the ways to fill in the `?`s so the runs of `#` have the lengths in `groups`.
+    fn arrangements(springs: &str, groups: &[usize]) -> (u64, Stats) {
+        let springs = springs.as_bytes();
+        // the number of arrangements of springs[at..] with groups[group..]
+        let count = |count: &mut dyn FnMut((usize, usize)) -> u64, (at, group): (usize, usize)| {
+            let Some(&spring) = springs.get(at) else {
+                return u64::from(group == groups.len());
+            };
+            let mut total = 0;
+            if spring != b'#' {
+                total += count((at + 1, group));
+            }
+            if let Some(&len) = groups.get(group) {
+                let end = at + len;
+                let fits = end <= springs.len()
+                    && !springs[at..end].contains(&b'.')
+                    && springs.get(end) != Some(&b'#');
+                if spring != b'.' && fits {
+                    total += count(((end + 1).min(springs.len()), group + 1));
+                }
+            }
+            total
+        };
+        let mut memo = Memo::new();
+        (memo.call((0, 0), &count), memo.stats())
+    }
+
+    #[test]
+    fn counts_arrangements() {
+        assert_eq!(arrangements("???.###", &[1, 1, 3]).0, 1);
+        assert_eq!(arrangements("?###????????", &[3, 2, 1]).0, 10);
+
+        let unfolded = ["?###????????"; 5].join("?");
+        let (count, stats) = arrangements(&unfolded, &[3, 2, 1].repeat(5));
+        assert_eq!(count, 506_250);
+        assert!(stats.hits > 0);
+        assert_eq!(stats.evictions, 0);
+    }
+
+    #[test]
+    fn bounded_capacity() {
+        let mut memo = Memo::bounded(2);
+        for key in 0..5 {
+            memo.insert(key, key * 10);
+        }
+        memo.insert(4, 40);
+        assert_eq!(memo.len(), 2);
+        assert_eq!(memo.get(&2), None);
+        assert_eq!(memo.get(&3), Some(30));
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 1,
+                misses: 1,
+                evictions: 3
+            }
+        );
+
+        // evicted values are simply computed again
+        let triangle = |triangle: &mut dyn FnMut(u64) -> u64, n: u64| {
+            if n == 0 {
+                0
+            } else {
+                n + triangle(n - 1)
+            }
+        };
+        let mut memo = Memo::bounded(1);
+        assert_eq!(memo.call(100, &triangle), 5050);
+        assert_eq!(memo.call(99, &triangle), 4950);
+        assert_eq!(memo.len(), 1);
+        let mut none = Memo::bounded(0);
+        assert_eq!(none.call(10, &triangle), 55);
+        assert!(none.is_empty());
+        none.clear();
+        assert_eq!(none.stats(), Stats::default());
+    }
+
+    #[test]
+    fn separate_caches() {
+        let calls = std::cell::Cell::new(0);
+        let square = |_: &mut dyn FnMut(u32) -> u32, n: u32| {
+            calls.set(calls.get() + 1);
+            n * n
+        };
+        let (mut first, mut second) = (memoize(square), memoize(square));
+        assert_eq!((first(3), first(3), second(3)), (9, 9, 9));
+        assert_eq!(calls.get(), 2);
+    }
+}
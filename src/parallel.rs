@@ -0,0 +1,187 @@
+/// Module that spreads work over scoped threads, one contiguous chunk per thread, keeping results
+/// in input order. The thread count comes from `set_threads`, `AOC_THREADS` or the number of cores.
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+
+/// The thread count set with [`set_threads`], `0` when it hasn't been.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the number of threads the helpers use for the rest of the process. `0` goes back to
+/// the default.
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// The number of threads the helpers split work over.
+pub fn threads() -> usize {
+    static DEFAULT: OnceLock<usize> = OnceLock::new();
+    match THREADS.load(Ordering::Relaxed) {
+        0 => *DEFAULT.get_or_init(|| {
+            std::env::var("AOC_THREADS")
+                .ok()
+                .and_then(|threads| threads.parse().ok())
+                .filter(|&threads| threads > 0)
+                .or_else(|| thread::available_parallelism().ok().map(usize::from))
+                .unwrap_or(1)
+        }),
+        threads => threads,
+    }
+}
+
+/// Runs `f` on each chunk on its own thread, and returns the results in order. A panic in `f`
+/// is passed on to the caller.
+fn scoped<'a, T: Sync + 'a, U: Send>(
+    chunks: impl Iterator<Item = &'a [T]>,
+    f: impl Fn(&'a [T]) -> U + Sync,
+) -> Vec<U> {
+    let f = &f;
+    thread::scope(|scope| {
+        // spawn every thread before joining the first one
+        #[allow(clippy::needless_collect)]
+        let handles: Vec<_> = chunks.map(|chunk| scope.spawn(move || f(chunk))).collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+/// The length of the chunks that split `len` items over the threads, or `None` if the work
+/// should stay on the calling thread.
+fn chunk_len(len: usize) -> Option<usize> {
+    let threads = threads().min(len);
+    (threads > 1).then(|| len.div_ceil(threads))
+}
+
+/// Returns `f` applied to each item, in order.
+pub fn par_map<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync) -> Vec<U> {
+    let Some(chunk_len) = chunk_len(items.len()) else {
+        return items.iter().map(f).collect();
+    };
+    scoped(items.chunks(chunk_len), |chunk| {
+        chunk.iter().map(&f).collect::<Vec<_>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Folds each thread's share of `items` into a value starting from `identity()`, and combines
+/// those values in order with `combine`.
+pub fn par_reduce<T: Sync, A: Send>(
+    items: &[T],
+    identity: impl Fn() -> A + Sync,
+    fold: impl Fn(A, &T) -> A + Sync,
+    combine: impl Fn(A, A) -> A,
+) -> A {
+    let Some(chunk_len) = chunk_len(items.len()) else {
+        return items.iter().fold(identity(), fold);
+    };
+    scoped(items.chunks(chunk_len), |chunk| {
+        chunk.iter().fold(identity(), &fold)
+    })
+    .into_iter()
+    .reduce(combine)
+    .unwrap_or_else(identity)
+}
+
+/// Splits `input` into about `pieces` chunks of whole lines.
+fn line_chunks(input: &str, pieces: usize) -> Vec<&str> {
+    let mut chunks = Vec::with_capacity(pieces);
+    let mut rest = input;
+    for remaining in (1..=pieces).rev() {
+        if rest.is_empty() {
+            break;
+        }
+        // cut after the first line break that leaves at least an even share of what's left
+        let target = rest.len().div_ceil(remaining);
+        let end = rest.as_bytes()[target - 1..]
+            .iter()
+            .position(|&c| c == b'\n')
+            .map_or(rest.len(), |newline| target + newline);
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+/// Returns `f` applied to each line of `input`, in order. Each thread gets a chunk of whole
+/// lines, so the lines are never collected first.
+pub fn par_lines<U: Send>(input: &str, f: impl Fn(&str) -> U + Sync) -> Vec<U> {
+    let threads = threads();
+    if threads == 1 {
+        return input.lines().map(f).collect();
+    }
+    let chunks = line_chunks(input, threads);
+    scoped(chunks.chunks(1), |chunk| {
+        chunk[0].lines().map(&f).collect::<Vec<_>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_lines_into_chunks() {
+        let input = "one\ntwo\nthree\nfour\nfive\n";
+        for pieces in 1..8 {
+            let chunks = line_chunks(input, pieces);
+            assert!(chunks.len() <= pieces);
+            assert_eq!(chunks.concat(), input);
+            assert!(chunks.iter().all(|chunk| chunk.ends_with('\n')));
+        }
+        assert_eq!(line_chunks("a\nb", 2), ["a\n", "b"]);
+        assert!(line_chunks("", 4).is_empty());
+    }
+
+    #[test]
+    fn keeps_results_in_order() {
+        let numbers: Vec<u64> = (0..10_000).collect();
+        let input: String = numbers.iter().map(|n| format!("{n}\r\n")).collect();
+        let expected: Vec<u64> = numbers.iter().map(|n| n * 3).collect();
+        let sum: u64 = numbers.iter().sum();
+
+        // the count is process wide, but every test gets the same results whatever it is
+        for threads in [1, 3, 16] {
+            set_threads(threads);
+            assert_eq!(par_map(&numbers, |n| n * 3), expected);
+            assert_eq!(
+                par_lines(&input, |line| line.parse::<u64>().unwrap() * 3),
+                expected
+            );
+            assert_eq!(par_reduce(&numbers, || 0, |a, &n| a + n, |a, b| a + b), sum);
+            assert_eq!(
+                par_reduce(&[] as &[u64], || 7, |a, &n| a + n, |a, b| a + b),
+                7
+            );
+            assert!(par_map(&[] as &[u8], |&n| n).is_empty());
+        }
+        set_threads(0);
+        assert!(threads() >= 1);
+    }
+
+    #[test]
+    fn combines_in_order() {
+        set_threads(4);
+        let words = ["a", "b", "c", "d", "e", "f", "g"];
+        let joined = par_reduce(&words, String::new, |s, w| s + w, |a, b| a + &b);
+        assert_eq!(joined, "abcdefg");
+    }
+
+    #[test]
+    #[should_panic(expected = "boom")]
+    fn passes_on_panics() {
+        set_threads(2);
+        par_map(&[1, 2, 3, 4], |&n| assert!(n < 4, "boom"));
+    }
+}
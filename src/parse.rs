@@ -0,0 +1,481 @@
+/// Module that parses numbers from the byte iterators solutions walk their input with.
+use std::error::Error;
+use std::fmt::Display;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+
+/// An integer type that numbers can be parsed into.
+pub trait Integer: Copy + Default {
+    /// Whether a leading `-` is part of the number.
+    const SIGNED: bool;
+
+    /// How many digits always fit, so they can be read without overflow checks.
+    const SAFE_DIGITS: u32;
+
+    /// Returns `self * 10 + digit`, wrapping on overflow.
+    #[must_use]
+    fn wrapping_push_digit(self, digit: u8) -> Self;
+
+    /// Returns `self * 10 - digit`, wrapping on overflow.
+    #[must_use]
+    fn wrapping_push_negative_digit(self, digit: u8) -> Self;
+
+    /// Returns `self * 10 + digit`, or [`None`] on overflow.
+    fn checked_push_digit(self, digit: u8) -> Option<Self>;
+
+    /// Returns `self * 10 - digit`, or [`None`] on overflow.
+    /// Negative numbers are accumulated downwards so `MIN` can be parsed.
+    fn checked_push_negative_digit(self, digit: u8) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($signed:literal: $($t:ty),*) => {$(
+        #[allow(clippy::cast_lossless, clippy::cast_possible_wrap)]
+        impl Integer for $t {
+            const SIGNED: bool = $signed;
+            const SAFE_DIGITS: u32 = <$t>::MAX.ilog10();
+
+            #[inline]
+            fn wrapping_push_digit(self, digit: u8) -> Self {
+                self.wrapping_mul(10).wrapping_add(digit as Self)
+            }
+
+            #[inline]
+            fn wrapping_push_negative_digit(self, digit: u8) -> Self {
+                self.wrapping_mul(10).wrapping_sub(digit as Self)
+            }
+
+            #[inline]
+            fn checked_push_digit(self, digit: u8) -> Option<Self> {
+                self.checked_mul(10)?.checked_add(digit as Self)
+            }
+
+            #[inline]
+            fn checked_push_negative_digit(self, digit: u8) -> Option<Self> {
+                self.checked_mul(10)?.checked_sub(digit as Self)
+            }
+        }
+    )*};
+}
+
+impl_integer!(false: u8, u16, u32, u64, u128, usize);
+impl_integer!(true: i8, i16, i32, i64, i128, isize);
+
+/// How spaces (`b' '`) around and inside a number are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spaces {
+    /// The number has to start at the current position; a space ends it.
+    Strict,
+    /// Spaces before the number are skipped; a space ends it.
+    Leading,
+    /// Spaces are skipped everywhere, e.g. `"7  15   30"` reads as `71530`.
+    Ignore,
+}
+
+/// An error which can be returned when reading a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// There were no digits at the current position.
+    NoDigits,
+    /// The number does not fit in the requested type. All of its digits were consumed.
+    Overflow,
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoDigits => f.write_str("expected a number"),
+            Self::Overflow => f.write_str("number does not fit in the requested type"),
+        }
+    }
+}
+
+/// Reads a number and returns it along with the byte that ended it (which has been consumed).
+#[inline]
+pub(crate) fn read_terminated<'a, T: Integer, I: Iterator<Item = &'a u8>>(
+    input: &mut I,
+    spaces: Spaces,
+) -> (Result<T, ParseError>, Option<u8>) {
+    let mut c = input.next().copied();
+    if spaces != Spaces::Strict {
+        while c == Some(b' ') {
+            c = input.next().copied();
+        }
+    }
+
+    let negative = T::SIGNED && c == Some(b'-');
+    if T::SIGNED && matches!(c, Some(b'-' | b'+')) {
+        c = input.next().copied();
+    }
+
+    if negative {
+        read_digits(
+            input,
+            c,
+            spaces,
+            T::wrapping_push_negative_digit,
+            T::checked_push_negative_digit,
+        )
+    } else {
+        read_digits(
+            input,
+            c,
+            spaces,
+            T::wrapping_push_digit,
+            T::checked_push_digit,
+        )
+    }
+}
+
+/// Reads the digits of a number whose first byte `c` has already been taken from `input`.
+#[inline]
+fn read_digits<'a, T: Integer, I: Iterator<Item = &'a u8>>(
+    input: &mut I,
+    mut c: Option<u8>,
+    spaces: Spaces,
+    push: impl Fn(T, u8) -> T,
+    checked_push: impl Fn(T, u8) -> Option<T>,
+) -> (Result<T, ParseError>, Option<u8>) {
+    if spaces == Spaces::Ignore {
+        while c == Some(b' ') {
+            c = input.next().copied();
+        }
+    }
+
+    let first = match c {
+        Some(d @ b'0'..=b'9') => d - b'0',
+        end => return (Err(ParseError::NoDigits), end),
+    };
+
+    let mut number = push(T::default(), first);
+    // these digits always fit, only check for overflow after them.
+    let mut unchecked = T::SAFE_DIGITS - 1;
+    while unchecked > 0 {
+        match input.next() {
+            Some(&c) if c.is_ascii_digit() => {
+                number = push(number, c - b'0');
+                unchecked -= 1;
+            }
+            Some(b' ') if spaces == Spaces::Ignore => {}
+            end => return (Ok(number), end.copied()),
+        }
+    }
+
+    let mut overflow = false;
+    for &c in input {
+        if c.is_ascii_digit() {
+            match checked_push(number, c - b'0') {
+                Some(n) if !overflow => number = n,
+                _ => overflow = true,
+            }
+        } else if c != b' ' || spaces != Spaces::Ignore {
+            return (finish(number, overflow), Some(c));
+        }
+    }
+    (finish(number, overflow), None)
+}
+
+#[inline]
+fn finish<T>(number: T, overflow: bool) -> Result<T, ParseError> {
+    if overflow {
+        Err(ParseError::Overflow)
+    } else {
+        Ok(number)
+    }
+}
+
+/// Reads a number starting at the current position, see [`Spaces`] for how spaces are handled.
+/// The byte after the number is consumed.
+#[inline]
+pub fn try_read_with<'a, T: Integer, I: Iterator<Item = &'a u8>>(
+    input: &mut I,
+    spaces: Spaces,
+) -> Result<T, ParseError> {
+    read_terminated(input, spaces).0
+}
+
+/// Reads a number starting at the current position, see [`Spaces`] for how spaces are handled.
+/// Returns [`None`] if there is no number or it does not fit in `T`.
+#[inline]
+pub fn read_with<'a, T: Integer, I: Iterator<Item = &'a u8>>(
+    input: &mut I,
+    spaces: Spaces,
+) -> Option<T> {
+    try_read_with(input, spaces).ok()
+}
+
+/// Reads a number that starts exactly at the current position.
+/// Returns [`None`] if there is no number or it does not fit in `T`.
+#[inline]
+pub fn read<'a, T: Integer, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<T> {
+    read_with(input, Spaces::Strict)
+}
+
+/// Skips ahead to the next number, wherever it is, and reads it.
+/// Returns [`None`] if the input ends first or the number does not fit in `T`.
+pub fn next_number<'a, T: Integer, I: Iterator<Item = &'a u8>>(input: &mut I) -> Option<T> {
+    let mut numbers = LineNumbers::<T, I> {
+        input,
+        any_line: true,
+        done: false,
+        overflowed: false,
+        phantom: PhantomData,
+    };
+    numbers.next()
+}
+
+/// Returns an iterator over the numbers on the current line, skipping everything in between.
+/// The newline that ends the line is consumed.
+///
+/// ```
+/// # use advent_of_code::parse;
+/// let mut input = b"0 -3 6\n9 x12\n".iter();
+/// let first: Vec<i32> = parse::line_numbers(&mut input).collect();
+/// let second: Vec<i32> = parse::line_numbers(&mut input).collect();
+/// assert_eq!((first, second), (vec![0, -3, 6], vec![9, 12]));
+/// ```
+pub fn line_numbers<'a, 'i, T: Integer, I: Iterator<Item = &'a u8>>(
+    input: &'i mut I,
+) -> LineNumbers<'i, T, I> {
+    LineNumbers {
+        input,
+        any_line: false,
+        done: false,
+        overflowed: false,
+        phantom: PhantomData,
+    }
+}
+
+/// An iterator over the numbers on one line, see [`line_numbers`].
+/// It stops early at a number that does not fit in `T`, and skips the rest of the line.
+pub struct LineNumbers<'i, T, I> {
+    input: &'i mut I,
+    any_line: bool,
+    done: bool,
+    overflowed: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T, I> LineNumbers<'_, T, I> {
+    /// Whether it stopped at a number that does not fit in `T`, rather than at the end of the line.
+    pub const fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+}
+
+impl<'a, T: Integer, I: Iterator<Item = &'a u8>> Iterator for LineNumbers<'_, T, I> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            let (result, end) = match self.input.next().copied() {
+                None => break,
+                Some(b'\n') if !self.any_line => break,
+                c @ Some(b'0'..=b'9') => read_digits(
+                    self.input,
+                    c,
+                    Spaces::Strict,
+                    T::wrapping_push_digit,
+                    T::checked_push_digit,
+                ),
+                Some(b'-') if T::SIGNED => match self.input.next().copied() {
+                    None => break,
+                    Some(b'\n') if !self.any_line => break,
+                    c @ Some(b'0'..=b'9') => read_digits(
+                        self.input,
+                        c,
+                        Spaces::Strict,
+                        T::wrapping_push_negative_digit,
+                        T::checked_push_negative_digit,
+                    ),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            self.done = end.is_none() || (end == Some(b'\n') && !self.any_line) || result.is_err();
+            if matches!(result, Err(ParseError::Overflow)) {
+                self.overflowed = true;
+                if !self.any_line && !matches!(end, None | Some(b'\n')) {
+                    // leave the input at the next line, like a line read to the end would
+                    self.input.find(|&&c| c == b'\n');
+                }
+            }
+            return result.ok();
+        }
+
+        self.done = true;
+        None
+    }
+}
+
+/// Returns an iterator over all numbers in `input`, skipping everything in between.
+/// It stops early at a number that does not fit in `T`.
+///
+/// ```
+/// # use advent_of_code::parse;
+/// let numbers: Vec<u64> = parse::numbers(b"Time: 7 15\nDistance: 9 40").collect();
+/// assert_eq!(numbers, vec![7, 15, 9, 40]);
+/// ```
+pub fn numbers<T: Integer>(input: &[u8]) -> Numbers<'_, T> {
+    Numbers {
+        input,
+        pos: 0,
+        phantom: PhantomData,
+    }
+}
+
+/// An iterator over all numbers in a byte slice, see [`numbers`].
+pub struct Numbers<'a, T> {
+    input: &'a [u8],
+    pos: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Integer> Iterator for Numbers<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let input = self.input;
+        let mut pos = self.pos;
+
+        let start = loop {
+            match input.get(pos)? {
+                b'0'..=b'9' => break pos,
+                b'-' if T::SIGNED && input.get(pos + 1).is_some_and(u8::is_ascii_digit) => {
+                    break pos
+                }
+                _ => pos += 1,
+            }
+        };
+
+        let mut iter = input[start..].iter();
+        let result = try_read_with(&mut iter, Spaces::Strict);
+        // stay at the end after a number that overflows
+        self.pos = if result.is_ok() {
+            input.len() - iter.as_slice().len()
+        } else {
+            input.len()
+        };
+        result.ok()
+    }
+}
+
+impl<T: Integer> FusedIterator for Numbers<'_, T> {}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_consecutive_numbers() {
+        let mut input = b"79 14 55\nx".iter();
+        assert_eq!(read::<u32, _>(&mut input), Some(79));
+        assert_eq!(read::<u32, _>(&mut input), Some(14));
+        assert_eq!(read::<u32, _>(&mut input), Some(55));
+        assert_eq!(input.next(), Some(&b'x'));
+    }
+
+    #[test]
+    fn spaces_policy() {
+        let text = b"  7  15   30\n";
+        assert_eq!(read::<u32, _>(&mut text.iter()), None);
+        assert_eq!(
+            read_with::<u32, _>(&mut text.iter(), Spaces::Leading),
+            Some(7)
+        );
+        assert_eq!(
+            read_with::<u32, _>(&mut text.iter(), Spaces::Ignore),
+            Some(71530)
+        );
+    }
+
+    #[test]
+    fn signed_numbers() {
+        assert_eq!(read::<i32, _>(&mut b"-42".iter()), Some(-42));
+        assert_eq!(read::<i32, _>(&mut b"+42".iter()), Some(42));
+        assert_eq!(read::<i8, _>(&mut b"-128".iter()), Some(i8::MIN));
+        assert_eq!(read::<u32, _>(&mut b"-42".iter()), None);
+        assert_eq!(read::<i32, _>(&mut b"-x".iter()), None);
+    }
+
+    #[test]
+    fn detects_overflow() {
+        assert_eq!(
+            try_read_with::<u8, _>(&mut b"255".iter(), Spaces::Strict),
+            Ok(255)
+        );
+        assert_eq!(
+            try_read_with::<u8, _>(&mut b"256".iter(), Spaces::Strict),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            try_read_with::<i8, _>(&mut b"-129".iter(), Spaces::Strict),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            try_read_with::<u64, _>(&mut b"".iter(), Spaces::Strict),
+            Err(ParseError::NoDigits)
+        );
+
+        // the whole number is consumed even if it overflows.
+        let mut input = b"99999999999 1".iter();
+        assert_eq!(read::<u32, _>(&mut input), None);
+        assert_eq!(read::<u32, _>(&mut input), Some(1));
+
+        // the rest of a line is skipped after a number that overflows.
+        let mut input = b"1 99999999999 3 4\n5 6\n".iter();
+        let mut numbers = line_numbers::<u32, _>(&mut input);
+        assert_eq!(numbers.by_ref().collect::<Vec<_>>(), vec![1]);
+        assert!(numbers.overflowed());
+        let mut numbers = line_numbers::<u32, _>(&mut input);
+        assert_eq!(numbers.by_ref().collect::<Vec<_>>(), vec![5, 6]);
+        assert!(!numbers.overflowed());
+        assert_eq!(line_numbers::<u32, _>(&mut input).count(), 0);
+    }
+
+    #[test]
+    fn numbers_in_lines() {
+        let mut input = b"Game 12: 3 blue, -4 red\n\n5 - 6".iter();
+        assert_eq!(
+            line_numbers::<i32, _>(&mut input).collect::<Vec<_>>(),
+            vec![12, 3, -4]
+        );
+        assert_eq!(line_numbers::<i32, _>(&mut input).count(), 0);
+        assert_eq!(
+            line_numbers::<i32, _>(&mut input).collect::<Vec<_>>(),
+            vec![5, 6]
+        );
+        assert_eq!(line_numbers::<i32, _>(&mut input).count(), 0);
+    }
+
+    #[test]
+    fn next_number_crosses_lines() {
+        let mut input = b"seeds:\n\n  -12 x".iter();
+        assert_eq!(next_number::<u32, _>(&mut input), Some(12));
+        assert_eq!(next_number::<u32, _>(&mut input), None);
+        assert_eq!(next_number::<i32, _>(&mut b"a\n-3".iter()), Some(-3));
+    }
+
+    #[test]
+    fn all_numbers() {
+        let input = b"x=-3, y=4..-10\nz=340282366920938463463374607431768211456";
+        assert_eq!(numbers::<i64>(input).collect::<Vec<_>>(), vec![-3, 4, -10]);
+        assert_eq!(
+            numbers::<u64>(input).take(3).collect::<Vec<_>>(),
+            vec![3, 4, 10]
+        );
+
+        // nothing after a number that overflows
+        let mut numbers = numbers::<u8>(b"1 256 3");
+        assert_eq!(numbers.next(), Some(1));
+        assert_eq!(numbers.next(), None);
+        assert_eq!(numbers.next(), None);
+    }
+}
@@ -0,0 +1,180 @@
+/// Module with the area and lattice points of polygons with integer vertices.
+use crate::geometry::{Direction, Point};
+use crate::math::gcd;
+use crate::rational::Rational;
+
+/// Where a point is relative to a [`Polygon`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A closed polygon through integer vertices; the last vertex connects back to the first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Polygon {
+    vertices: Vec<Point<i64>>,
+}
+
+impl Polygon {
+    pub fn new(vertices: impl IntoIterator<Item = Point<i64>>) -> Self {
+        Self {
+            vertices: vertices.into_iter().collect(),
+        }
+    }
+
+    /// Traces a polygon from `start`, moving the given number of steps in each direction.
+    /// Unit moves are `(dir, 1)`.
+    pub fn from_moves<D: Direction>(
+        start: Point<i64>,
+        moves: impl IntoIterator<Item = (D, i64)>,
+    ) -> Self {
+        let mut vertices = vec![start];
+        let mut position = start;
+        for (dir, steps) in moves {
+            let delta = dir.delta();
+            position += Point::new(i64::from(delta.x) * steps, i64::from(delta.y) * steps);
+            vertices.push(position);
+        }
+        if vertices.len() > 1 && vertices.last() == vertices.first() {
+            vertices.pop();
+        }
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point<i64>] {
+        &self.vertices
+    }
+
+    /// The edges, as pairs of their end points.
+    fn edges(&self) -> impl Iterator<Item = (Point<i128>, Point<i128>)> + '_ {
+        let widen = |p: &Point<i64>| Point::new(i128::from(p.x), i128::from(p.y));
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices
+            .iter()
+            .zip(next)
+            .map(move |(a, b)| (widen(a), widen(b)))
+    }
+
+    /// Twice the signed area, using the shoelace formula. It is positive when the vertices go
+    /// clockwise on screen, where y points down.
+    pub fn twice_signed_area(&self) -> i128 {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+    }
+
+    pub fn area(&self) -> Rational {
+        Rational::new(self.twice_signed_area().abs(), 2)
+    }
+
+    /// The number of lattice points on the edges.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| gcd((b.x - a.x).abs(), (b.y - a.y).abs()))
+            .sum()
+    }
+
+    /// The number of lattice points strictly inside the polygon, using Pick's theorem:
+    /// `area = interior + boundary / 2 - 1`. Only meaningful if the edges don't cross.
+    pub fn interior_points(&self) -> i128 {
+        let twice_area = self.twice_signed_area().abs();
+        ((twice_area - self.boundary_points()) / 2 + 1).max(0)
+    }
+
+    /// Returns whether `point` is inside, on the edge of or outside the polygon.
+    pub fn locate(&self, point: Point<i64>) -> Location {
+        let p = Point::new(i128::from(point.x), i128::from(point.y));
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            // > 0 if p is to the right of a -> b, on screen
+            let cross = (b.x - a.x) * (p.y - a.y) - (p.x - a.x) * (b.y - a.y);
+            let within = |low: i128, high: i128, v: i128| low.min(high) <= v && v <= low.max(high);
+            if cross == 0 && within(a.x, b.x, p.x) && within(a.y, b.y, p.y) {
+                return Location::Boundary;
+            }
+            // count the edges crossed by a ray going from p towards +x
+            if (a.y > p.y) != (b.y > p.y) && (cross > 0) == (b.y > a.y) {
+                inside = !inside;
+            }
+        }
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::geometry::Dir4;
+
+    fn points(vertices: &[(i64, i64)]) -> Polygon {
+        Polygon::new(vertices.iter().map(|&(x, y)| Point::new(x, y)))
+    }
+
+    #[test]
+    fn area_and_orientation() {
+        let square = points(&[(0, 0), (2, 0), (2, 2), (0, 2)]);
+        assert_eq!(square.twice_signed_area(), 8);
+        let reversed = points(&[(0, 2), (2, 2), (2, 0), (0, 0)]);
+        assert_eq!(reversed.twice_signed_area(), -8);
+        assert_eq!(reversed.area(), Rational::from(4));
+
+        let triangle = points(&[(0, 0), (3, 0), (0, 1)]);
+        assert_eq!(triangle.area(), Rational::new(3, 2));
+        assert_eq!(triangle.boundary_points(), 5);
+        assert_eq!(triangle.interior_points(), 0);
+        assert_eq!(Polygon::default().area(), Rational::ZERO);
+    }
+
+    #[test]
+    fn picks_theorem() {
+        // an L shape, with collinear vertices along the edges
+        let l_shape = points(&[(0, 0), (2, 0), (4, 0), (4, 2), (2, 2), (2, 4), (0, 4)]);
+        assert_eq!(l_shape.twice_signed_area(), 24);
+        assert_eq!(l_shape.boundary_points(), 16);
+        assert_eq!(l_shape.interior_points(), 5);
+
+        let far = points(&[
+            (i64::MIN, i64::MIN),
+            (i64::MAX, i64::MIN),
+            (i64::MAX, i64::MAX),
+        ]);
+        assert_eq!(far.boundary_points(), 3 * (i128::from(u64::MAX)));
+    }
+
+    #[test]
+    fn moves() {
+        let unit = [Dir4::E, Dir4::E, Dir4::S, Dir4::W, Dir4::W, Dir4::N].map(|dir| (dir, 1));
+        let traced = Polygon::from_moves(Point::new(5, 5), unit);
+        assert_eq!(traced.vertices().len(), 6);
+        assert_eq!(traced.twice_signed_area(), 4);
+        assert_eq!(traced.boundary_points(), 6);
+        assert_eq!(traced.interior_points(), 0);
+    }
+
+    #[test]
+    fn locate_points() {
+        let l_shape = points(&[(0, 0), (4, 0), (4, 2), (2, 2), (2, 4), (0, 4)]);
+        let locate = |x, y| l_shape.locate(Point::new(x, y));
+        assert_eq!(locate(1, 1), Location::Inside);
+        assert_eq!(locate(1, 3), Location::Inside);
+        assert_eq!(locate(3, 1), Location::Inside);
+        assert_eq!(locate(3, 3), Location::Outside);
+        assert_eq!(locate(5, 0), Location::Outside);
+        assert_eq!(locate(-1, 2), Location::Outside);
+        // level with a vertex, where the ray touches the corner
+        assert_eq!(locate(1, 2), Location::Inside);
+        assert_eq!(locate(-1, 0), Location::Outside);
+        assert_eq!(locate(0, 0), Location::Boundary);
+        assert_eq!(locate(3, 2), Location::Boundary);
+        assert_eq!(locate(2, 3), Location::Boundary);
+        let inside = (-1..6)
+            .flat_map(|x| (-1..6).map(move |y| (x, y)))
+            .filter(|&(x, y)| locate(x, y) == Location::Inside)
+            .count();
+        assert_eq!(inside as i128, l_shape.interior_points());
+    }
+}
@@ -0,0 +1,319 @@
+/// Module with exact fractions over `i128` and a linear equation solver that uses them.
+use crate::math::gcd;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A fraction kept in lowest terms, with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self { numer: 0, denom: 1 };
+    pub const ONE: Self = Self { numer: 1, denom: 1 };
+
+    /// Returns `numer / denom`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denom` is zero, or the fraction can't be represented.
+    pub fn new(numer: i128, denom: i128) -> Self {
+        Self::checked_new(numer, denom).expect("invalid fraction")
+    }
+
+    /// Returns `numer / denom`, or [`None`] if `denom` is zero or the fraction can't be
+    /// represented with a positive denominator.
+    pub fn checked_new(numer: i128, denom: i128) -> Option<Self> {
+        if denom == 0 {
+            return None;
+        }
+        let common = gcd(numer.checked_abs()?, denom.checked_abs()?);
+        let (numer, denom) = (numer / common, denom / common);
+        if denom < 0 {
+            Some(Self {
+                numer: numer.checked_neg()?,
+                denom: -denom,
+            })
+        } else {
+            Some(Self { numer, denom })
+        }
+    }
+
+    pub fn numer(self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denom == 1
+    }
+
+    /// Returns the value if it is an integer.
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    /// Returns the largest integer that is at most `self`.
+    pub fn floor(self) -> i128 {
+        self.numer.div_euclid(self.denom)
+    }
+
+    /// Returns the smallest integer that is at least `self`.
+    pub fn ceil(self) -> i128 {
+        self.floor() + i128::from(self.numer.rem_euclid(self.denom) != 0)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numer: self.numer.checked_neg()?,
+            denom: self.denom,
+        })
+    }
+
+    /// Returns `1 / self`, or [`None`] if `self` is zero.
+    pub fn checked_recip(self) -> Option<Self> {
+        Self::checked_new(self.denom, self.numer)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let common = gcd(self.denom, other.denom);
+        let numer = self
+            .numer
+            .checked_mul(other.denom / common)?
+            .checked_add(other.numer.checked_mul(self.denom / common)?)?;
+        Self::checked_new(numer, (self.denom / common).checked_mul(other.denom)?)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        // cancelling first keeps the products as small as possible
+        let first = gcd(self.numer, other.denom);
+        let second = gcd(other.numer, self.denom);
+        Self::checked_new(
+            (self.numer / first).checked_mul(other.numer / second)?,
+            (self.denom / second).checked_mul(other.denom / first)?,
+        )
+    }
+
+    /// Returns `self / other`, or [`None`] on overflow or if `other` is zero.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.checked_mul(other.checked_recip()?)
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Rational {
+            fn from(n: $t) -> Self {
+                Self {
+                    numer: i128::from(n),
+                    denom: 1,
+                }
+            }
+        }
+    )*};
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+macro_rules! impl_op {
+    ($($op:ident, $method:ident, $checked:ident, $message:literal;)*) => {$(
+        impl $op for Rational {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                self.$checked(other).expect($message)
+            }
+        }
+    )*};
+}
+
+impl_op!(
+    Add, add, checked_add, "attempt to add with overflow";
+    Sub, sub, checked_sub, "attempt to subtract with overflow";
+    Mul, mul, checked_mul, "attempt to multiply with overflow";
+    Div, div, checked_div, "attempt to divide by zero or with overflow";
+);
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("attempt to negate with overflow")
+    }
+}
+
+/// Compares `a / b` with `c / d` for positive `b` and `d`, like a continued fraction so nothing
+/// is multiplied and nothing can overflow.
+fn compare_fractions(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let (whole_ab, rest_ab) = (a.div_euclid(b), a.rem_euclid(b));
+    let (whole_cd, rest_cd) = (c.div_euclid(d), c.rem_euclid(d));
+    match (whole_ab.cmp(&whole_cd), rest_ab, rest_cd) {
+        (Ordering::Equal, 0, 0) => Ordering::Equal,
+        (Ordering::Equal, 0, _) => Ordering::Less,
+        (Ordering::Equal, _, 0) => Ordering::Greater,
+        // rest_ab / b < rest_cd / d exactly when b / rest_ab > d / rest_cd
+        (Ordering::Equal, _, _) => compare_fractions(d, rest_cd, b, rest_ab),
+        (ordering, _, _) => ordering,
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_fractions(self.numer, self.denom, other.numer, other.denom)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+/// Solves the linear equations `matrix * x = rhs` exactly by Gaussian elimination.
+///
+/// Each row of `matrix` holds the coefficients of one equation. Returns [`None`] if there isn't
+/// exactly one solution, the rows have the wrong length, or a calculation overflows.
+pub fn solve<T: Into<Rational> + Copy>(matrix: &[Vec<T>], rhs: &[T]) -> Option<Vec<Rational>> {
+    let n = rhs.len();
+    if matrix.len() != n || matrix.iter().any(|row| row.len() != n) {
+        return None;
+    }
+    // the augmented matrix, with `rhs` as the last column
+    let mut rows: Vec<Vec<Rational>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, &b)| row.iter().chain([&b]).map(|&v| v.into()).collect())
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|&row| rows[row][col] != Rational::ZERO)?;
+        rows.swap(col, pivot);
+        let pivot_row = rows[col].clone();
+        for (row, values) in rows.iter_mut().enumerate() {
+            if row == col || values[col] == Rational::ZERO {
+                continue;
+            }
+            let factor = values[col].checked_div(pivot_row[col])?;
+            for (value, &pivot_value) in values.iter_mut().zip(&pivot_row).skip(col) {
+                *value = value.checked_sub(factor.checked_mul(pivot_value)?)?;
+            }
+        }
+    }
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| row[n].checked_div(row[i]))
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let half = Rational::new(1, 2);
+        let third = Rational::new(-2, -6);
+        assert_eq!((third.numer(), third.denom()), (1, 3));
+        assert_eq!(Rational::new(3, -6), -half);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!(Rational::checked_new(1, 0), None);
+        assert_eq!(Rational::ZERO.checked_recip(), None);
+        assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+        assert_eq!(half.to_integer(), None);
+        assert_eq!(half.to_string(), "1/2");
+        assert_eq!((-Rational::from(4)).to_string(), "-4");
+    }
+
+    #[test]
+    fn rounding() {
+        assert_eq!(Rational::new(7, 2).floor(), 3);
+        assert_eq!(Rational::new(7, 2).ceil(), 4);
+        assert_eq!(Rational::new(-7, 2).floor(), -4);
+        assert_eq!(Rational::new(-7, 2).ceil(), -3);
+        assert_eq!(Rational::from(5).ceil(), 5);
+    }
+
+    #[test]
+    fn overflow() {
+        let big = Rational::from(i128::MAX);
+        assert_eq!(big.checked_add(Rational::ONE), None);
+        assert_eq!(big.checked_mul(Rational::from(2)), None);
+        assert_eq!(Rational::from(i128::MIN).checked_neg(), None);
+        // cancelling before multiplying avoids overflow
+        let tiny = Rational::new(1, i128::MAX);
+        assert_eq!(big * tiny, Rational::ONE);
+        assert_eq!(
+            Rational::new(i128::MAX, 2) - Rational::new(i128::MAX - 2, 2),
+            Rational::ONE
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        let mut values = [
+            Rational::new(1, 2),
+            Rational::new(-1, 3),
+            Rational::new(i128::MAX, i128::MAX - 1),
+            Rational::new(i128::MAX - 1, i128::MAX - 2),
+            Rational::ZERO,
+            Rational::from(2),
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            [
+                Rational::new(-1, 3),
+                Rational::ZERO,
+                Rational::new(1, 2),
+                Rational::new(i128::MAX, i128::MAX - 1),
+                Rational::new(i128::MAX - 1, i128::MAX - 2),
+                Rational::from(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn linear_solver() {
+        // x + 2y + 3z = 14, 2x - y + z = 3, 3x + y - z = 2
+        let matrix = [vec![1, 2, 3], vec![2, -1, 1], vec![3, 1, -1]];
+        let solution = solve(&matrix, &[14, 3, 2]).unwrap();
+        assert_eq!(solution, [1, 2, 3].map(Rational::from));
+
+        // needs a row swap, and has a fractional answer
+        let solution = solve(&[vec![0, 2], vec![3, 1]], &[1, 1]).unwrap();
+        assert_eq!(solution, [Rational::new(1, 6), Rational::new(1, 2)]);
+
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 6]), None);
+        assert_eq!(solve(&[vec![1, 2]], &[3]), None);
+        assert_eq!(solve::<i64>(&[], &[]), Some(vec![]));
+    }
+}
@@ -0,0 +1,324 @@
+/// Module that parses fixed-format lines with the `scan!` pattern macro.
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::parse::{self, Integer, ParseError, Spaces};
+
+/// Matches literals and typed placeholders against a [`Scanner`] and returns the values of the
+/// placeholders (a single value, or a tuple if there are several).
+///
+/// Literals are string or byte string literals that have to match exactly, placeholders are
+/// types implementing [`Scan`] in braces, e.g. `{u32}` or `{&[u8]}`. The scanner is advanced
+/// past everything that matched, even if a later part of the pattern does not.
+///
+/// The first argument is a [`Scanner`] (or `&mut Scanner`), e.g. `scan!(Scanner::new(line), ...)`
+/// for a one-off match.
+#[macro_export]
+macro_rules! scan {
+    (@munch $scanner:ident [$($value:ident)*]) => {
+        ($($value),*)
+    };
+    (@munch $scanner:ident [$($value:ident)*] $literal:literal $($rest:tt)*) => {{
+        $scanner.literal($literal)?;
+        $crate::scan!(@munch $scanner [$($value)*] $($rest)*)
+    }};
+    (@munch $scanner:ident [$($value:ident)*] {$type:ty} $($rest:tt)*) => {{
+        let value: $type = $scanner.value()?;
+        $crate::scan!(@munch $scanner [$($value)* value] $($rest)*)
+    }};
+    ($scanner:expr, $($pattern:tt)+) => {
+        $crate::scan::run($scanner.by_ref(), |scanner| {
+            Ok($crate::scan!(@munch scanner [] $($pattern)+))
+        })
+    };
+}
+
+/// Runs the body of a [`scan!`](crate::scan!) so its closure gets the right lifetimes.
+#[doc(hidden)]
+pub fn run<'a, T>(
+    scanner: &mut Scanner<'a>,
+    body: impl FnOnce(&mut Scanner<'a>) -> Result<T, ScanError>,
+) -> Result<T, ScanError> {
+    body(scanner)
+}
+
+/// What a [`Scanner`] expected to find when it failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    Literal(&'static [u8]),
+    Number(ParseError),
+    Word,
+    Bytes(usize),
+}
+
+/// A mismatch between a pattern and the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanError {
+    /// Offset of the mismatch in bytes from the start of the input.
+    pub position: usize,
+    /// 1-based line of the mismatch.
+    pub line: usize,
+    /// 1-based column (in bytes) of the mismatch.
+    pub column: usize,
+    pub expected: Expected,
+    /// The byte found at the mismatch, [`None`] at the end of the input.
+    pub found: Option<u8>,
+}
+
+impl Error for ScanError {}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.expected {
+            Expected::Literal(literal) => write!(f, "expected `{}`", literal.escape_ascii())?,
+            Expected::Number(error) => write!(f, "{error}")?,
+            Expected::Word => f.write_str("expected a word")?,
+            Expected::Bytes(count) => write!(f, "expected {count} more bytes")?,
+        }
+        match self.found {
+            Some(found) => write!(f, ", found `{}`", found.escape_ascii()),
+            None => f.write_str(", found the end of the input"),
+        }
+    }
+}
+
+/// A cursor over a byte slice that patterns are matched against.
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a (impl AsRef<[u8]> + ?Sized)) -> Self {
+        Self {
+            input: input.as_ref(),
+            position: 0,
+        }
+    }
+
+    /// Borrows the scanner, so [`scan!`](crate::scan!) works with both `Scanner` and `&mut Scanner`.
+    pub fn by_ref(&mut self) -> &mut Self {
+        self
+    }
+
+    /// Offset of the next byte from the start of the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The input that has not been scanned yet.
+    pub fn rest(&self) -> &'a [u8] {
+        &self.input[self.position..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    pub fn next_byte(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    /// Consumes `literal`, or fails without consuming anything.
+    pub fn literal(
+        &mut self,
+        literal: &'static (impl AsRef<[u8]> + ?Sized),
+    ) -> Result<(), ScanError> {
+        let literal = literal.as_ref();
+        if self.eat(literal) {
+            return Ok(());
+        }
+        let matching = self
+            .rest()
+            .iter()
+            .zip(literal)
+            .take_while(|(a, b)| a == b)
+            .count();
+        Err(self.error_at(self.position + matching, Expected::Literal(literal)))
+    }
+
+    /// Consumes `literal` if the input continues with it.
+    pub fn eat(&mut self, literal: &[u8]) -> bool {
+        let matches = self.rest().starts_with(literal);
+        if matches {
+            self.position += literal.len();
+        }
+        matches
+    }
+
+    /// Scans a value of type `T`, see [`Scan`].
+    pub fn value<T: Scan<'a>>(&mut self) -> Result<T, ScanError> {
+        T::scan(self)
+    }
+
+    /// Consumes the rest of the current line and returns it without the line ending.
+    /// Returns [`None`] at the end of the input.
+    pub fn line(&mut self) -> Option<&'a [u8]> {
+        if self.is_empty() {
+            return None;
+        }
+        let rest = self.rest();
+        let len = rest.iter().position(|&c| c == b'\n').unwrap_or(rest.len());
+        self.position += (len + 1).min(rest.len());
+        let line = &rest[..len];
+        Some(line.strip_suffix(b"\r").unwrap_or(line))
+    }
+
+    /// Returns an error for a mismatch at the current position.
+    pub fn error(&self, expected: Expected) -> ScanError {
+        self.error_at(self.position, expected)
+    }
+
+    fn error_at(&self, position: usize, expected: Expected) -> ScanError {
+        let before = &self.input[..position];
+        let line_start = before
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map_or(0, |i| i + 1);
+        ScanError {
+            position,
+            line: before.split(|&c| c == b'\n').count(),
+            column: position - line_start + 1,
+            expected,
+            found: self.input.get(position).copied(),
+        }
+    }
+}
+
+/// A value that can be read by a [`Scanner`] and used as a placeholder in [`scan!`](crate::scan!).
+pub trait Scan<'a>: Sized {
+    fn scan(scanner: &mut Scanner<'a>) -> Result<Self, ScanError>;
+}
+
+/// Integers are read with [`parse`], an optional `-` is part of signed numbers.
+impl<'a, T: Integer> Scan<'a> for T {
+    fn scan(scanner: &mut Scanner<'a>) -> Result<Self, ScanError> {
+        let rest = scanner.rest();
+        let mut input = rest.iter();
+        let (result, end) = parse::read_terminated(&mut input, Spaces::Strict);
+        let consumed = rest.len() - input.as_slice().len() - usize::from(end.is_some());
+        match result {
+            Ok(number) => {
+                scanner.position += consumed;
+                Ok(number)
+            }
+            Err(ParseError::NoDigits) => Err(scanner.error(Expected::Number(ParseError::NoDigits))),
+            Err(error) => {
+                Err(scanner.error_at(scanner.position + consumed, Expected::Number(error)))
+            }
+        }
+    }
+}
+
+/// A word is a non-empty run of ASCII letters and digits.
+impl<'a> Scan<'a> for &'a [u8] {
+    fn scan(scanner: &mut Scanner<'a>) -> Result<Self, ScanError> {
+        let rest = scanner.rest();
+        let len = rest
+            .iter()
+            .position(|c| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(scanner.error(Expected::Word));
+        }
+        scanner.position += len;
+        Ok(&rest[..len])
+    }
+}
+
+/// A word, see the implementation for `&[u8]`.
+impl<'a> Scan<'a> for &'a str {
+    fn scan(scanner: &mut Scanner<'a>) -> Result<Self, ScanError> {
+        let word: &[u8] = scanner.value()?;
+        // ASCII letters and digits are always valid UTF-8.
+        Ok(std::str::from_utf8(word).unwrap_or_default())
+    }
+}
+
+/// Exactly `N` bytes, whatever they are.
+impl<'a, const N: usize> Scan<'a> for [u8; N] {
+    fn scan(scanner: &mut Scanner<'a>) -> Result<Self, ScanError> {
+        let Some(bytes) = scanner.rest().first_chunk() else {
+            let missing = N - scanner.rest().len();
+            return Err(scanner.error_at(scanner.input.len(), Expected::Bytes(missing)));
+        };
+        scanner.position += N;
+        Ok(*bytes)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_values_between_literals() {
+        let mut scanner = Scanner::new("Game 12: 3 blue, -4 red");
+        let (game, count, color) = scan!(scanner, "Game " {u32} ": " {u8} " " {&str}).unwrap();
+        assert_eq!((game, count, color), (12, 3, "blue"));
+        assert_eq!(scan!(scanner, ", " {i64} " red").unwrap(), -4);
+        assert!(scanner.is_empty());
+    }
+
+    #[test]
+    fn works_on_borrowed_and_temporary_scanners() {
+        fn node(scanner: &mut Scanner) -> Result<[u8; 3], ScanError> {
+            scan!(scanner, {[u8; 3]} " = ")
+        }
+        let mut scanner = Scanner::new(b"AAA = BBB = ");
+        assert_eq!(node(&mut scanner), Ok(*b"AAA"));
+        assert_eq!(node(&mut scanner), Ok(*b"BBB"));
+
+        let values: (u8, u8) = scan!(Scanner::new("1,2"), {_} "," {_}).unwrap();
+        assert_eq!(values, (1, 2));
+        assert_eq!(scan!(Scanner::new("abc"), "abc"), Ok(()));
+    }
+
+    #[test]
+    fn reports_mismatch_positions() {
+        let mut scanner = Scanner::new("a: 1\nb: x");
+        scan!(scanner, "a: " {u32} "\n").unwrap();
+        let error = scan!(scanner, "b: " {u32}).unwrap_err();
+        assert_eq!(
+            error,
+            ScanError {
+                position: 8,
+                line: 2,
+                column: 4,
+                expected: Expected::Number(ParseError::NoDigits),
+                found: Some(b'x'),
+            }
+        );
+
+        let error = scan!(Scanner::new("Game 1;"), "Game 1: ").unwrap_err();
+        assert_eq!((error.column, error.found), (7, Some(b';')));
+
+        let error = scan!(Scanner::new("ab"), { [u8; 3] }).unwrap_err();
+        assert_eq!(error.expected, Expected::Bytes(1));
+        assert_eq!(error.found, None);
+    }
+
+    #[test]
+    fn reports_overflow() {
+        let error = scan!(Scanner::new("x 300 y"), "x " {u8}).unwrap_err();
+        assert_eq!(error.expected, Expected::Number(ParseError::Overflow));
+        assert_eq!(error.position, 5);
+    }
+
+    #[test]
+    fn reads_lines() {
+        let mut scanner = Scanner::new("a\r\n\nb");
+        assert_eq!(scanner.line(), Some(&b"a"[..]));
+        assert_eq!(scanner.line(), Some(&b""[..]));
+        assert_eq!(scanner.line(), Some(&b"b"[..]));
+        assert_eq!(scanner.line(), None);
+    }
+}
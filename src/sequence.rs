@@ -0,0 +1,227 @@
+/// Module that extrapolates polynomial sequences with finite differences, checking for overflow.
+use crate::math::gcd;
+
+/// The finite differences of a sequence, built up one value at a time.
+///
+/// Only the first and last value of each row of differences are kept, so pushing a value takes
+/// time proportional to the number of rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DifferenceTable {
+    /// The first value of each row: the Newton forward coefficients.
+    leading: Vec<i128>,
+    /// The last value of each row.
+    trailing: Vec<i128>,
+    /// Whether each row has a value other than zero.
+    nonzero: Vec<bool>,
+    len: usize,
+    overflowed: bool,
+}
+
+impl DifferenceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of values pushed so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether a difference overflowed, after which nothing can be extrapolated.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// The first value of each row of differences, starting with the first value pushed.
+    pub fn leading(&self) -> &[i128] {
+        &self.leading
+    }
+
+    /// Appends the next value of the sequence.
+    pub fn push(&mut self, value: impl Into<i128>) {
+        self.len += 1;
+        if self.overflowed {
+            return;
+        }
+        let mut value = value.into();
+        for (row, last) in self.trailing.iter_mut().enumerate() {
+            let Some(difference) = value.checked_sub(*last) else {
+                self.overflowed = true;
+                return;
+            };
+            *last = value;
+            self.nonzero[row] |= value != 0;
+            value = difference;
+        }
+        self.leading.push(value);
+        self.trailing.push(value);
+        self.nonzero.push(value != 0);
+    }
+
+    /// Returns the degree of the polynomial the values follow, found as the first row of
+    /// differences that is all zeros. A sequence of zeros has degree `0`.
+    ///
+    /// Returns [`None`] if no row is all zeros yet, so there aren't enough values to tell.
+    pub fn degree(&self) -> Option<usize> {
+        let zero_row = self.nonzero.iter().position(|&nonzero| !nonzero)?;
+        Some(zero_row.saturating_sub(1))
+    }
+
+    /// Returns the value at `index` of the lowest degree polynomial through all the values, where
+    /// the first value pushed has index `0`. Negative indices extrapolate backwards.
+    ///
+    /// Returns [`None`] if the table is empty or a calculation overflows.
+    pub fn value_at(&self, index: i64) -> Option<i128> {
+        if self.is_empty() || self.overflowed {
+            return None;
+        }
+        let index = i128::from(index);
+        let terms = self
+            .leading
+            .iter()
+            .rposition(|&c| c != 0)
+            .map_or(0, |i| i + 1);
+        // Newton's forward formula: the sum of binomial(index, j) times the j-th difference.
+        let mut binomial = 1i128;
+        let mut value = 0i128;
+        for (j, &coefficient) in (0..).zip(&self.leading[..terms]) {
+            if j > 0 {
+                binomial = binomial.checked_mul(index - (j - 1))? / j;
+            }
+            value = value.checked_add(binomial.checked_mul(coefficient)?)?;
+        }
+        Some(value)
+    }
+}
+
+impl<T: Into<i128>> Extend<T> for DifferenceTable {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Into<i128>> FromIterator<T> for DifferenceTable {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut table = Self::new();
+        table.extend(iter);
+        table
+    }
+}
+
+/// Returns the value at `index` of the lowest degree polynomial through `values`, which are at
+/// indices `0`, `1`, and so on.
+pub fn extrapolate<T: Into<i128>>(values: impl IntoIterator<Item = T>, index: i64) -> Option<i128> {
+    values
+        .into_iter()
+        .collect::<DifferenceTable>()
+        .value_at(index)
+}
+
+/// Returns the value at `x` of the lowest degree polynomial through `points`, given as `(x, y)`
+/// with distinct `x`s that don't have to be evenly spaced.
+///
+/// Returns [`None`] if the value isn't an integer, an `x` repeats or a calculation overflows.
+pub fn lagrange(points: &[(i64, i64)], x: i64) -> Option<i128> {
+    let x = i128::from(x);
+    // the sum so far, as a reduced fraction
+    let (mut numerator, mut denominator) = (0i128, 1i128);
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let xi = i128::from(xi);
+        let (mut term_numerator, mut term_denominator) = (i128::from(yi), 1i128);
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let xj = i128::from(xj);
+            if xi == xj {
+                return None;
+            }
+            term_numerator = term_numerator.checked_mul(x - xj)?;
+            term_denominator = term_denominator.checked_mul(xi - xj)?;
+            let common = gcd(term_numerator, term_denominator);
+            (term_numerator, term_denominator) =
+                (term_numerator / common, term_denominator / common);
+        }
+        numerator = numerator
+            .checked_mul(term_denominator)?
+            .checked_add(term_numerator.checked_mul(denominator)?)?;
+        denominator = denominator.checked_mul(term_denominator)?;
+        let common = gcd(numerator, denominator);
+        (numerator, denominator) = (numerator / common, denominator / common);
+    }
+    numerator
+        .checked_rem(denominator)
+        .filter(|&rem| rem == 0)
+        .map(|_| numerator / denominator)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differences() {
+        let table: DifferenceTable = [10, 13, 16, 21, 30, 45].into_iter().collect();
+        assert_eq!(table.leading(), [10, 3, 0, 2, 0, 0]);
+        assert_eq!(table.degree(), Some(3));
+        assert_eq!(table.len(), 6);
+
+        let degree = |values: &[i64]| values.iter().copied().collect::<DifferenceTable>().degree();
+        assert_eq!(degree(&[0, 3, 6, 9]), Some(1));
+        assert_eq!(degree(&[5, 5, 5]), Some(0));
+        assert_eq!(degree(&[0, 0]), Some(0));
+        assert_eq!(degree(&[1, 2, 4]), None);
+        assert_eq!(degree(&[]), None);
+    }
+
+    #[test]
+    fn extrapolates() {
+        let values = [10, 13, 16, 21, 30, 45];
+        assert_eq!(extrapolate(values, 6), Some(68));
+        assert_eq!(extrapolate(values, -1), Some(5));
+        for (i, &value) in values.iter().enumerate() {
+            assert_eq!(extrapolate(values, i as i64), Some(i128::from(value)));
+        }
+        // the cube of n, far away in both directions
+        let cubes = (0..5i64).map(|n| n * n * n);
+        let n = i64::from(i32::MAX);
+        assert_eq!(extrapolate(cubes.clone(), n), Some(i128::from(n).pow(3)));
+        assert_eq!(extrapolate(cubes.clone(), -n), Some(-i128::from(n).pow(3)));
+        assert_eq!(extrapolate(cubes, i64::MAX), None);
+        assert_eq!(extrapolate::<i64>([], 0), None);
+    }
+
+    #[test]
+    fn overflow_is_detected() {
+        // the differences double in size with every row
+        let alternating = (0..200).map(|i| if i % 2 == 0 { i64::MAX } else { i64::MIN });
+        let table: DifferenceTable = alternating.collect();
+        assert!(table.overflowed());
+        assert_eq!(table.len(), 200);
+        assert_eq!(table.value_at(0), None);
+    }
+
+    #[test]
+    fn lagrange_points() {
+        // x^2 + 1, unevenly spaced
+        let points = [(0, 1), (2, 5), (5, 26)];
+        assert_eq!(lagrange(&points, 3), Some(10));
+        assert_eq!(lagrange(&points, -1), Some(2));
+        assert_eq!(lagrange(&points, 5), Some(26));
+        assert_eq!(lagrange(&[(0, 0), (2, 1)], 1), None);
+        assert_eq!(lagrange(&[(0, 0), (0, 1)], 1), None);
+        assert_eq!(lagrange(&[], 1), Some(0));
+        let evenly: Vec<_> = [10, 13, 16, 21, 30, 45]
+            .into_iter()
+            .zip(0..)
+            .map(|(y, x)| (x, y))
+            .collect();
+        assert_eq!(lagrange(&evenly, -1), Some(5));
+    }
+}
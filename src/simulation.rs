@@ -0,0 +1,285 @@
+/// Module that steps a double-buffered state with a rule, and skips ahead whole cycles once
+/// the states repeat.
+use std::hash::{BuildHasher, Hash};
+
+use crate::collections::{FastHashMap, FxBuildHasher};
+use crate::cycle::Cycle;
+use crate::grid::{Grid, Pos};
+
+type Observer<'o, S> = Box<dyn FnMut(u64, &S) + 'o>;
+
+/// A state that is stepped by a rule, generation by generation.
+pub struct Simulation<'o, S, R> {
+    current: S,
+    next: S,
+    rule: R,
+    generation: u64,
+    /// Each generation so far, until the cycle is found.
+    history: Vec<S>,
+    /// The generations with each hash. Different states can share a hash.
+    seen: FastHashMap<u64, Vec<usize>>,
+    cycle: Option<Cycle>,
+    observers: Vec<Observer<'o, S>>,
+}
+
+impl<'o, S, R> Simulation<'o, S, R>
+where
+    S: Clone + Hash + PartialEq,
+    R: FnMut(&S, &mut S),
+{
+    /// Starts at generation `0` with `initial`.
+    ///
+    /// The rule gets the current state and the state to write the next generation into. That
+    /// one holds an older generation, so the rule has to overwrite all of it.
+    pub fn new(initial: S, rule: R) -> Self {
+        let mut simulation = Self {
+            next: initial.clone(),
+            current: initial,
+            rule,
+            generation: 0,
+            history: vec![],
+            seen: FastHashMap::default(),
+            cycle: None,
+            observers: vec![],
+        };
+        simulation.record();
+        simulation
+    }
+
+    /// Calls `observer` with the generation and state after every step, for debugging or
+    /// drawing the states. After a [`jump_to`](Self::jump_to) it sees only the generation
+    /// jumped to.
+    pub fn observe(&mut self, observer: impl FnMut(u64, &S) + 'o) {
+        self.observers.push(Box::new(observer));
+    }
+
+    pub fn state(&self) -> &S {
+        &self.current
+    }
+
+    pub fn into_state(self) -> S {
+        self.current
+    }
+
+    /// The number of steps taken so far.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The cycle the states repeat in, once it has been found. Its `start` is the first
+    /// generation that is part of the cycle.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Checks the current generation against the earlier ones, and keeps it if it's new. The
+    /// first state that repeats is the start of the cycle, since the rule decides the rest.
+    fn record(&mut self) {
+        if self.cycle.is_some() {
+            return;
+        }
+        let hash = FxBuildHasher::default().hash_one(&self.current);
+        let generation = self.history.len();
+        let seen = self.seen.entry(hash).or_default();
+        if let Some(&start) = seen.iter().find(|&&g| self.history[g] == self.current) {
+            self.cycle = Some(Cycle {
+                start,
+                length: generation - start,
+            });
+            self.history = vec![];
+            self.seen = FastHashMap::default();
+            return;
+        }
+        seen.push(generation);
+        self.history.push(self.current.clone());
+    }
+
+    /// Applies the rule once, without recording or observing the new generation.
+    fn advance(&mut self) {
+        (self.rule)(&self.current, &mut self.next);
+        std::mem::swap(&mut self.current, &mut self.next);
+    }
+
+    fn notify(&mut self) {
+        for observer in &mut self.observers {
+            observer(self.generation, &self.current);
+        }
+    }
+
+    /// Steps one generation and returns the new state.
+    pub fn step(&mut self) -> &S {
+        self.advance();
+        self.generation += 1;
+        self.record();
+        self.notify();
+        &self.current
+    }
+
+    /// Steps `generations` times, one by one.
+    pub fn run(&mut self, generations: u64) -> &S {
+        for _ in 0..generations {
+            self.step();
+        }
+        &self.current
+    }
+
+    /// Steps until a generation is the same as the one before, and returns the generation the
+    /// state settled at. Loops forever if it never does.
+    pub fn run_until_stable(&mut self) -> u64 {
+        loop {
+            self.step();
+            if self.current == self.next {
+                break self.generation - 1;
+            }
+        }
+    }
+
+    /// Moves to the state at generation `target`, stepping until the states repeat and then
+    /// skipping whole cycles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` is before the current generation and the states haven't repeated yet,
+    /// or if `target` is before the start of the cycle. Earlier states aren't kept, so only a
+    /// known cycle lets it go back.
+    pub fn jump_to(&mut self, target: u64) -> &S {
+        while self.generation < target && self.cycle.is_none() {
+            self.step();
+        }
+        if self.generation != target {
+            let cycle = self
+                .cycle
+                .filter(|cycle| target >= cycle.start as u64)
+                .expect("can't step back to a generation before the cycle");
+            let length = cycle.length as u64;
+            // the same point in the cycle as `target`, at or after the current generation
+            let steps = (target % length + length - self.generation % length) % length;
+            for _ in 0..steps {
+                self.advance();
+            }
+            self.generation = target;
+            self.notify();
+        }
+        &self.current
+    }
+}
+
+/// Turns a rule that computes each cell of the next generation from the current grid into a
+/// rule for [`Simulation`].
+pub fn cellwise<T>(mut cell: impl FnMut(&Grid<T>, Pos) -> T) -> impl FnMut(&Grid<T>, &mut Grid<T>) {
+    move |current, next| {
+        for row in 0..current.height() {
+            for col in 0..current.width() {
+                next[(row, col)] = cell(current, (row, col));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Tilts every round rock (`O`) as far north as it goes, then turns the platform clockwise.
+    fn tilt_north_and_turn(grid: &Grid<u8>) -> Grid<u8> {
+        let mut grid = grid.clone();
+        for col in 0..grid.width() {
+            let mut free = 0;
+            for row in 0..grid.height() {
+                match grid[(row, col)] {
+                    b'#' => free = row + 1,
+                    b'O' => {
+                        grid[(row, col)] = b'.';
+                        grid[(free, col)] = b'O';
+                        free += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        grid.rotate_cw()
+    }
+
+    fn north_load(grid: &Grid<u8>) -> usize {
+        grid.find_all(&b'O')
+            .map(|(row, _)| grid.height() - row)
+            .sum()
+    }
+
+    const PLATFORM: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[test]
+    fn jumps_ahead_through_cycles() {
+        // a spin tilts north, west, south and east
+        let spin = |current: &Grid<u8>, next: &mut Grid<u8>| {
+            *next = (0..4).fold(current.clone(), |grid, _| tilt_north_and_turn(&grid));
+        };
+        let mut platform = Simulation::new(Grid::parse(PLATFORM).unwrap(), spin);
+        assert_eq!(north_load(platform.jump_to(1_000_000_000)), 64);
+        let cycle = platform.cycle().unwrap();
+        assert_eq!((cycle.start, cycle.length), (3, 7));
+        assert_eq!(platform.generation(), 1_000_000_000);
+
+        // stepping back into the cycle works, and agrees with stepping one by one
+        platform.jump_to(5);
+        let mut slow = Simulation::new(Grid::parse(PLATFORM).unwrap(), spin);
+        assert_eq!(platform.state(), slow.run(5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn cannot_go_back_before_the_cycle() {
+        let mut counter = Simulation::new(0u8, |&n, next: &mut u8| *next = (n + 1).min(5));
+        counter.jump_to(10);
+        counter.jump_to(2);
+    }
+
+    #[test]
+    fn observes_generations() {
+        let seen = RefCell::new(vec![]);
+        let mut still = Simulation::new(0u32, |&n, next: &mut u32| *next = n);
+        let mut doubling = Simulation::new(1u32, |&n, next: &mut u32| *next = (n * 2).min(100));
+        doubling.observe(|generation, &n| seen.borrow_mut().push((generation, n)));
+        assert_eq!(doubling.run_until_stable(), 7);
+        assert_eq!(doubling.into_state(), 100);
+        assert_eq!(seen.borrow()[..3], [(1, 2), (2, 4), (3, 8)]);
+        assert_eq!(seen.borrow().len(), 8);
+        assert_eq!(still.run_until_stable(), 0);
+
+        // a jump is observed where it lands
+        seen.borrow_mut().clear();
+        let mut counter = Simulation::new(0u32, |&n, next: &mut u32| *next = (n + 1) % 3);
+        counter.observe(|generation, &n| seen.borrow_mut().push((generation, n)));
+        counter.jump_to(100);
+        assert_eq!(seen.borrow().last(), Some(&(100, 1)));
+        assert!(seen.borrow().windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn states_with_equal_hashes() {
+        /// Every value hashes the same, so only comparing them tells them apart.
+        #[derive(Clone, PartialEq)]
+        struct Collides(u8);
+        impl Hash for Collides {
+            fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+        }
+
+        let mut counter = Simulation::new(Collides(0), |n, next: &mut Collides| {
+            next.0 = if n.0 == 9 { 4 } else { n.0 + 1 };
+        });
+        assert_eq!(counter.jump_to(1_003).0, 7);
+        let cycle = counter.cycle().unwrap();
+        assert_eq!((cycle.start, cycle.length), (4, 6));
+    }
+}
@@ -0,0 +1,153 @@
+/// Module that reads inputs too big to load at once in chunks of whole lines.
+use std::io::{self, ErrorKind, Read};
+
+/// The buffer size of [`LineChunks::new`].
+const DEFAULT_CAPACITY: usize = 1 << 16;
+
+/// Splits what a reader produces into chunks that end after a line break, or at the end.
+#[derive(Debug)]
+pub struct LineChunks<R> {
+    reader: R,
+    /// Holds the last chunk and the start of the next one. Grows if a line doesn't fit.
+    buf: Vec<u8>,
+    /// The end of the last chunk.
+    start: usize,
+    /// The end of what has been read.
+    end: usize,
+}
+
+impl<R: Read> LineChunks<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(reader, DEFAULT_CAPACITY)
+    }
+
+    /// Reads up to `capacity` bytes at a time, more only when a line is longer than that.
+    pub fn with_capacity(reader: R, capacity: usize) -> Self {
+        Self {
+            reader,
+            buf: vec![0; capacity.max(1)],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Returns the next chunk of whole lines, or [`None`] at the end of the input. Only the last
+    /// chunk can end without a line break.
+    pub fn next_chunk(&mut self) -> io::Result<Option<&[u8]>> {
+        // move the part of a line after the last chunk to the front
+        self.buf.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+        loop {
+            if self.end == self.buf.len() {
+                // a line longer than the buffer
+                self.buf.resize(self.buf.len() * 2, 0);
+            }
+            let read = match self.reader.read(&mut self.buf[self.end..]) {
+                Ok(read) => read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if read == 0 {
+                self.start = self.end;
+                return Ok((self.end > 0).then(|| &self.buf[..self.end]));
+            }
+            let searched = self.end;
+            self.end += read;
+            if let Some(newline) = self.buf[searched..self.end]
+                .iter()
+                .rposition(|&c| c == b'\n')
+            {
+                self.start = searched + newline + 1;
+                return Ok(Some(&self.buf[..self.start]));
+            }
+        }
+    }
+
+    /// Folds every chunk into `init` with `f`.
+    pub fn fold<A>(mut self, init: A, mut f: impl FnMut(A, &[u8]) -> A) -> io::Result<A> {
+        let mut acc = init;
+        while let Some(chunk) = self.next_chunk()? {
+            acc = f(acc, chunk);
+        }
+        Ok(acc)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::swar;
+
+    /// Hands out at most `step` bytes per read, and an interruption before each of them.
+    struct Trickle<'a> {
+        input: &'a [u8],
+        step: usize,
+        interrupt: bool,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(ErrorKind::Interrupted.into());
+            }
+            let len = self.step.min(buf.len()).min(self.input.len());
+            buf[..len].copy_from_slice(&self.input[..len]);
+            self.input = &self.input[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn chunks_end_after_lines() {
+        let input = "short\r\nlines\n\na much longer line than the buffer\nno newline";
+        for capacity in [1, 3, 8, 100] {
+            for step in [1, 2, 5, 64] {
+                let reader = Trickle {
+                    input: input.as_bytes(),
+                    step,
+                    interrupt: false,
+                };
+                let mut chunks = LineChunks::with_capacity(reader, capacity);
+                let mut all = vec![];
+                let mut lines = vec![];
+                while let Some(chunk) = chunks.next_chunk().unwrap() {
+                    assert!(
+                        chunk.ends_with(b"\n") || all.len() + chunk.len() == input.len(),
+                        "{chunk:?}"
+                    );
+                    all.extend_from_slice(chunk);
+                    lines.extend(swar::lines(chunk).map(<[u8]>::to_vec));
+                }
+                assert_eq!(all, input.as_bytes());
+                let expected: Vec<_> = input.lines().map(|line| line.as_bytes().to_vec()).collect();
+                assert_eq!(lines, expected);
+                assert_eq!(chunks.next_chunk().unwrap(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn reads_nothing() {
+        assert_eq!(
+            LineChunks::new(io::empty()).fold(0, |n, _| n + 1).unwrap(),
+            0
+        );
+        let chunks = LineChunks::new(io::repeat(b'\n').take(200_000));
+        let lines = chunks.fold(0, |n, chunk| n + chunk.len()).unwrap();
+        assert_eq!(lines, 200_000);
+    }
+
+    #[test]
+    fn passes_on_errors() {
+        struct Broken;
+        impl Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(ErrorKind::BrokenPipe.into())
+            }
+        }
+        let error = LineChunks::new(Broken).fold((), |(), _| ()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    }
+}
@@ -0,0 +1,356 @@
+/// Module that scans bytes eight at a time, treating a `u64` as eight byte lanes (SIMD within a
+/// register). Run `cargo bench --bench swar` to compare with the byte-by-byte loops.
+use std::ops::Range;
+
+const ONES: u64 = 0x0101_0101_0101_0101;
+const HIGHS: u64 = 0x8080_8080_8080_8080;
+
+/// A word with `byte` in every lane.
+const fn splat(byte: u8) -> u64 {
+    ONES * byte as u64
+}
+
+fn load(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+/// Loads fewer than eight bytes, filling the missing lanes with zeros.
+fn load_padded(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .rev()
+        .fold(0, |word, &byte| word << 8 | u64::from(byte))
+}
+
+/// The index of the first lane with its high bit set in `mask`.
+fn first_lane(mask: u64) -> usize {
+    mask.trailing_zeros() as usize / 8
+}
+
+/// Sets the high bit of every lane that is zero.
+fn zero_lanes(word: u64) -> u64 {
+    // adding 0x7f to the low seven bits sets the high bit unless they are all zero
+    !(((word & !HIGHS) + !HIGHS) | word) & HIGHS
+}
+
+/// Sets the high bit of every lane that holds `byte`.
+fn lanes_equal(word: u64, byte: u8) -> u64 {
+    zero_lanes(word ^ splat(byte))
+}
+
+/// Sets the high bit of every lane that holds an ASCII digit.
+fn digit_lanes(word: u64) -> u64 {
+    let low = word & !HIGHS;
+    let at_least_zero = low + splat(0x80 - b'0');
+    let above_nine = low + splat(0x80 - b'9' - 1);
+    at_least_zero & !above_nine & !word & HIGHS
+}
+
+/// Finds the first byte of `haystack` whose lane is marked by `lanes`, or which passes `test`
+/// in the bytes after the last whole word.
+fn position(
+    haystack: &[u8],
+    lanes: impl Fn(u64) -> u64,
+    test: impl Fn(u8) -> bool,
+) -> Option<usize> {
+    let mut words = haystack.chunks_exact(8);
+    for (i, word) in words.by_ref().enumerate() {
+        let mask = lanes(load(word));
+        if mask != 0 {
+            return Some(i * 8 + first_lane(mask));
+        }
+    }
+    let rest = words.remainder();
+    let start = haystack.len() - rest.len();
+    rest.iter().position(|&c| test(c)).map(|i| start + i)
+}
+
+/// Returns the index of the first `needle` in `haystack`.
+pub fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+    position(haystack, |word| lanes_equal(word, needle), |c| c == needle)
+}
+
+/// Returns the index of the first ASCII digit in `haystack`.
+pub fn find_digit(haystack: &[u8]) -> Option<usize> {
+    position(haystack, digit_lanes, |c| c.is_ascii_digit())
+}
+
+/// Returns how many ASCII digits `haystack` starts with.
+pub fn digit_run(haystack: &[u8]) -> usize {
+    position(
+        haystack,
+        |word| !digit_lanes(word) & HIGHS,
+        |c| !c.is_ascii_digit(),
+    )
+    .unwrap_or(haystack.len())
+}
+
+/// Splits `input` into lines like [`str::lines`]: on `\n`, dropping a `\r` before it, and
+/// without an empty line after a final line break.
+pub fn lines(input: &[u8]) -> Lines<'_> {
+    Lines { rest: input }
+}
+
+/// The iterator returned by [`lines`].
+#[derive(Debug, Clone)]
+pub struct Lines<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let Some(end) = find_byte(b'\n', self.rest) else {
+            return Some(std::mem::take(&mut self.rest));
+        };
+        let line = &self.rest[..end];
+        self.rest = &self.rest[end + 1..];
+        Some(line.strip_suffix(b"\r").unwrap_or(line))
+    }
+}
+
+/// Returns the index ranges of the runs of ASCII digits in `input`.
+pub fn digit_runs(input: &[u8]) -> DigitRuns<'_> {
+    DigitRuns { input, at: 0 }
+}
+
+/// The iterator returned by [`digit_runs`].
+#[derive(Debug, Clone)]
+pub struct DigitRuns<'a> {
+    input: &'a [u8],
+    at: usize,
+}
+
+impl Iterator for DigitRuns<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.at + find_digit(&self.input[self.at..])?;
+        let end = start + digit_run(&self.input[start..]);
+        self.at = end;
+        Some(start..end)
+    }
+}
+
+/// Parses a right-aligned field of two bytes: two digits, or a space and a digit.
+pub fn parse_two_digits(field: [u8; 2]) -> Option<u8> {
+    let [tens, ones] = field;
+    ((tens == b' ' || tens.is_ascii_digit()) && ones.is_ascii_digit())
+        // a space has a low nibble of 0, like '0'
+        .then(|| (tens & 0xf) * 10 + (ones & 0xf))
+}
+
+/// Parses the two-byte fields (see [`parse_two_digits`]) that start every `stride` bytes in
+/// `input`.
+///
+/// That covers rows of numbers below 100 like `" 5 17 42"`. Parsing stops at the first field
+/// that isn't one.
+///
+/// # Panics
+///
+/// Panics if `stride` is below `2`, so fields would overlap.
+pub fn two_digit_fields(input: &[u8], stride: usize) -> TwoDigitFields<'_> {
+    assert!(stride >= 2, "two-digit fields need a stride of at least 2");
+    TwoDigitFields {
+        input,
+        stride,
+        per_word: 6 / stride + 1,
+        shift: u32::try_from(stride * 8).unwrap_or(u32::MAX),
+        lanes: (0..=6 / stride).fold(0, |lanes, field| lanes | 0x80 << (field * stride * 8)),
+        values: 0,
+        valid: 0,
+        buffered: 0,
+    }
+}
+
+/// The iterator returned by [`two_digit_fields`].
+#[derive(Debug, Clone)]
+pub struct TwoDigitFields<'a> {
+    /// The input after the buffered fields.
+    input: &'a [u8],
+    stride: usize,
+    /// How many fields start in the first seven lanes of a word.
+    per_word: usize,
+    /// How far to shift the lanes to get to the next field.
+    shift: u32,
+    /// The high bit of each lane a field starts at in a word.
+    lanes: u64,
+    /// The value of the field starting at each lane, with the next field in the lowest lane.
+    values: u64,
+    /// The high bit of each lane is set if the field starting there is valid.
+    valid: u64,
+    buffered: usize,
+}
+
+/// Parses the two-byte field starting at each lane of `word`, returning the values and a mask
+/// with the high bit of each lane set if its field is valid.
+fn fields_in(word: u64) -> (u64, u64) {
+    let digits = digit_lanes(word);
+    let tens = digits | lanes_equal(word, b' ');
+    // the low nibbles of digits and spaces are their values, so with the nibbles of the next
+    // lane added in, each lane holds the field that starts there
+    let nibbles = word & splat(0xf);
+    (nibbles * 10 + (nibbles >> 8), tens & (digits >> 8))
+}
+
+impl TwoDigitFields<'_> {
+    /// Parses every field starting in the next word at once.
+    fn refill(&mut self) -> Option<()> {
+        let (word, fields) = if let Some(&word) = self.input.first_chunk() {
+            (u64::from_le_bytes(word), self.per_word)
+        } else {
+            let fields = self.input.len().checked_sub(2)? / self.stride + 1;
+            (load_padded(self.input), fields.min(self.per_word))
+        };
+        (self.values, self.valid) = fields_in(word);
+        self.buffered = fields;
+        self.input = &self.input[(fields * self.stride).min(self.input.len())..];
+        Some(())
+    }
+
+    /// Moves on to the next field in the buffered word.
+    fn shift(&mut self) {
+        // a stride above 7 leaves a single field per word, which shifts everything out
+        self.values = self.values.checked_shr(self.shift).unwrap_or(0);
+        self.valid = self.valid.checked_shr(self.shift).unwrap_or(0);
+        self.buffered -= 1;
+    }
+}
+
+impl Iterator for TwoDigitFields<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffered == 0 {
+            self.refill()?;
+        }
+        if self.valid & 0x80 == 0 {
+            self.buffered = 0;
+            self.input = &[];
+            return None;
+        }
+        let value = self.values as u8;
+        self.shift();
+        Some(value)
+    }
+
+    fn fold<B, F: FnMut(B, u8) -> B>(mut self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        while self.buffered > 0 {
+            let Some(value) = self.next() else {
+                return acc;
+            };
+            acc = f(acc, value);
+        }
+        // whole words with only valid fields skip the per-field bookkeeping
+        while let Some(&word) = self.input.first_chunk() {
+            let (mut values, valid) = fields_in(u64::from_le_bytes(word));
+            if valid & self.lanes != self.lanes {
+                break;
+            }
+            for _ in 0..self.per_word {
+                acc = f(acc, values as u8);
+                values = values.checked_shr(self.shift).unwrap_or(0);
+            }
+            self.input = &self.input[(self.per_word * self.stride).min(self.input.len())..];
+        }
+        for value in self {
+            acc = f(acc, value);
+        }
+        acc
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    /// Every byte value at every position of a word, and after the whole words.
+    fn placements() -> impl Iterator<Item = (Vec<u8>, usize)> {
+        (0..=255).flat_map(|byte| {
+            (0..11).map(move |at| {
+                let mut bytes = vec![b'.'; 11];
+                bytes[at] = byte;
+                (bytes, at)
+            })
+        })
+    }
+
+    #[test]
+    fn agrees_with_byte_loops() {
+        for (bytes, at) in placements() {
+            let byte = bytes[at];
+            let expected = bytes.iter().position(|&c| c == byte);
+            assert_eq!(find_byte(byte, &bytes), expected, "{bytes:?}");
+            let expected = bytes.iter().position(u8::is_ascii_digit);
+            assert_eq!(find_digit(&bytes), expected, "{bytes:?}");
+
+            let mut digits = vec![b'7'; 11];
+            digits[at] = byte;
+            let expected = digits.iter().take_while(|c| c.is_ascii_digit()).count();
+            assert_eq!(digit_run(&digits), expected, "{digits:?}");
+        }
+        assert_eq!(find_byte(b'\n', b""), None);
+        assert_eq!(digit_run(b"1234567890123"), 13);
+    }
+
+    #[test]
+    fn splits_lines() {
+        for input in [
+            "",
+            "\n",
+            "a\nb",
+            "a\r\nbb\n\nccc\n",
+            "a long first line\r\nand a second one\n",
+            "\r",
+        ] {
+            let expected: Vec<_> = input.lines().map(str::as_bytes).collect();
+            assert_eq!(lines(input.as_bytes()).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn finds_digit_runs() {
+        let input = b"Game 12: 3 blue, 4567890123 red;8";
+        let runs: Vec<_> = digit_runs(input).map(|run| &input[run]).collect();
+        assert_eq!(runs, [&b"12"[..], b"3", b"4567890123", b"8"]);
+        assert_eq!(digit_runs(b"no digits at all").next(), None);
+    }
+
+    #[test]
+    fn parses_two_digit_fields() {
+        assert_eq!(parse_two_digits(*b"42"), Some(42));
+        assert_eq!(parse_two_digits(*b" 7"), Some(7));
+        assert_eq!(parse_two_digits(*b"7 "), None);
+        assert_eq!(parse_two_digits(*b"  "), None);
+
+        let numbers: Vec<u8> = (0..40u32).map(|n| (n * 7 % 100) as u8).collect();
+        for stride in 2..12 {
+            let mut input = vec![b' '; numbers.len() * stride - stride + 2];
+            for (i, &n) in numbers.iter().enumerate() {
+                input[i * stride..i * stride + 2].copy_from_slice(format!("{n:2}").as_bytes());
+            }
+            assert_eq!(
+                two_digit_fields(&input, stride).collect::<Vec<_>>(),
+                numbers
+            );
+            // folding takes whole words at once, also after a few fields one by one
+            let mut fields = two_digit_fields(&input, stride).map(u32::from);
+            fields.next();
+            let sum: u32 = numbers[1..].iter().map(|&n| u32::from(n)).sum();
+            assert_eq!(fields.sum::<u32>(), sum);
+
+            // a field that isn't one ends the numbers
+            input[5 * stride + 1] = b'|';
+            assert_eq!(two_digit_fields(&input, stride).count(), 5);
+        }
+        let row = b"83 86  6 31 17  9 48 53";
+        let numbers: Vec<_> = two_digit_fields(row, 3).collect();
+        assert_eq!(numbers, [83, 86, 6, 31, 17, 9, 48, 53]);
+        assert_eq!(two_digit_fields(b"4", 3).next(), None);
+    }
+}
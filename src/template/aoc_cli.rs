@@ -60,9 +60,9 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
         &[
             "--overwrite".into(),
             "--input-file".into(),
-            input_path.to_string(),
+            input_path.clone(),
             "--puzzle-file".into(),
-            puzzle_path.to_string(),
+            puzzle_path.clone(),
         ],
         day,
     );
@@ -87,11 +87,11 @@ fn get_input_path(day: Day) -> String {
     format!("data/inputs/{day}.txt")
 }
 
-fn get_puzzle_path(day: Day) -> String {
+pub fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
-fn get_year() -> Option<u16> {
+pub fn get_year() -> Option<u16> {
     std::env::var("AOC_YEAR").map_or(None, |x| x.parse().ok())
 }
 
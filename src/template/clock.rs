@@ -0,0 +1,195 @@
+/// Module that maps wall-clock time onto the puzzle unlock calendar.
+/// Puzzles unlock at midnight US-Eastern, which advent of code treats as a fixed UTC-5.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::Day;
+
+/// Offset of the puzzle unlock clock from UTC, in seconds.
+pub const UNLOCK_UTC_OFFSET_SECS: i64 = -5 * 60 * 60;
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A source of the current time.
+///
+/// Everything that depends on "now" takes a [`Clock`] so unlock logic can be tested with a [`FixedClock`].
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+/// A [`Clock`] backed by the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A [`Clock`] that always returns the same instant.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub SystemTime);
+
+impl FixedClock {
+    /// Creates a [`FixedClock`] at the given number of seconds after the unix epoch.
+    pub fn from_unix_secs(secs: u64) -> Self {
+        Self(UNIX_EPOCH + Duration::from_secs(secs))
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// A calendar date on the puzzle unlock clock (UTC-5).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PuzzleDate {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl PuzzleDate {
+    /// Returns the current date on the puzzle unlock clock.
+    pub fn now(clock: &impl Clock) -> Self {
+        Self::from_system_time(clock.now())
+    }
+
+    /// Converts an instant into a date on the puzzle unlock clock.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let local_secs = unix_secs(time) + UNLOCK_UTC_OFFSET_SECS;
+        let (year, month, day) = civil_from_days(local_secs.div_euclid(SECS_PER_DAY));
+        Self { year, month, day }
+    }
+}
+
+/// Returns the instant at which the puzzle for `day` of `year` unlocks.
+pub fn unlock_time(year: i32, day: Day) -> SystemTime {
+    let days = days_from_civil(year, 12, day.into_inner());
+    let secs = days * SECS_PER_DAY - UNLOCK_UTC_OFFSET_SECS;
+    from_unix_secs(secs)
+}
+
+/// Returns how long until the puzzle for `day` of `year` unlocks, or [`None`] if it is already available.
+pub fn time_until_unlock(clock: &impl Clock, year: i32, day: Day) -> Option<Duration> {
+    unlock_time(year, day)
+        .duration_since(clock.now())
+        .ok()
+        .filter(|d| !d.is_zero())
+}
+
+fn unix_secs(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs().cast_signed(),
+        Err(e) => -(e.duration().as_secs_f64().ceil() as i64),
+    }
+}
+
+fn from_unix_secs(secs: i64) -> SystemTime {
+    if secs >= 0 {
+        UNIX_EPOCH + Duration::from_secs(secs.unsigned_abs())
+    } else {
+        UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+    }
+}
+
+// adapted from http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// adapted from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+#[allow(clippy::cast_sign_loss)]
+fn civil_from_days(days: i64) -> (i32, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year as i32, month as u8, day as u8)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+
+    // 2023-12-01T05:00:00Z, the unlock of day 1 in 2023.
+    const UNLOCK_2023_DAY_1: u64 = 1_701_406_800;
+
+    #[test]
+    fn unlock_time_is_midnight_utc_minus_5() {
+        assert_eq!(
+            unlock_time(2023, day!(1)),
+            FixedClock::from_unix_secs(UNLOCK_2023_DAY_1).now()
+        );
+        assert_eq!(
+            unlock_time(2023, day!(25)),
+            FixedClock::from_unix_secs(UNLOCK_2023_DAY_1 + 24 * 86_400).now()
+        );
+    }
+
+    #[test]
+    fn puzzle_date_rolls_over_at_unlock() {
+        let before = FixedClock::from_unix_secs(UNLOCK_2023_DAY_1 - 1);
+        let after = FixedClock::from_unix_secs(UNLOCK_2023_DAY_1);
+        assert_eq!(
+            PuzzleDate::now(&before),
+            PuzzleDate {
+                year: 2023,
+                month: 11,
+                day: 30
+            }
+        );
+        assert_eq!(
+            PuzzleDate::now(&after),
+            PuzzleDate {
+                year: 2023,
+                month: 12,
+                day: 1
+            }
+        );
+    }
+
+    #[test]
+    fn puzzle_date_handles_leap_years() {
+        // 2024-02-29T12:00:00Z
+        let clock = FixedClock::from_unix_secs(1_709_208_000);
+        assert_eq!(
+            PuzzleDate::now(&clock),
+            PuzzleDate {
+                year: 2024,
+                month: 2,
+                day: 29
+            }
+        );
+    }
+
+    #[test]
+    fn time_until_unlock_counts_down() {
+        let clock = FixedClock::from_unix_secs(UNLOCK_2023_DAY_1 - 90);
+        assert_eq!(
+            time_until_unlock(&clock, 2023, day!(1)),
+            Some(Duration::from_secs(90))
+        );
+        let clock = FixedClock::from_unix_secs(UNLOCK_2023_DAY_1);
+        assert_eq!(time_until_unlock(&clock, 2023, day!(1)), None);
+        assert_eq!(
+            time_until_unlock(&clock, 2023, day!(2)),
+            Some(Duration::from_secs(86_400))
+        );
+    }
+}
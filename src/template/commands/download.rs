@@ -1,15 +1,79 @@
 use crate::template::aoc_cli;
+use crate::template::clock::{self, Clock, PuzzleDate, SystemClock};
+use crate::template::{ANSI_BOLD, ANSI_RESET};
 use crate::Day;
-use std::process;
+use std::io::{stdout, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{process, thread};
 
-pub fn handle(day: Day) {
+/// Upper bound for the random delay added after a puzzle unlocks, so not everyone hits the server at once.
+const MAX_JITTER_MILLIS: u32 = 3000;
+
+pub fn handle(day: Day, wait: bool) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
+    if wait {
+        wait_for_unlock(&SystemClock, day);
+    }
+
     if let Err(e) = aoc_cli::download(day) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
-    };
+    }
+}
+
+/// Blocks until the puzzle for `day` unlocks, printing a countdown in the meantime.
+pub fn wait_for_unlock(clock: &impl Clock, day: Day) {
+    let year = aoc_cli::get_year().map_or_else(|| PuzzleDate::now(clock).year, i32::from);
+
+    if clock::time_until_unlock(clock, year, day).is_none() {
+        return;
+    }
+
+    let mut stdout = stdout();
+    while let Some(remaining) = clock::time_until_unlock(clock, year, day) {
+        print!(
+            "\r⏳ Day {day} unlocks in {ANSI_BOLD}{}{ANSI_RESET} ",
+            format_countdown(remaining)
+        );
+        let _ = stdout.flush();
+        thread::sleep(remaining.min(Duration::from_secs(1)));
+    }
+
+    let jitter = jitter();
+    println!("\r🎄 Day {day} is unlocked, downloading in {jitter:.1?}...      ");
+    thread::sleep(jitter);
+}
+
+fn format_countdown(remaining: Duration) -> String {
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    format!("{hours:02}:{mins:02}:{secs:02}")
+}
+
+fn jitter() -> Duration {
+    // no need for a proper random number generator, the sub-second part of the clock is plenty.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    Duration::from_millis(u64::from(nanos % MAX_JITTER_MILLIS))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::format_countdown;
+    use std::time::Duration;
+
+    #[test]
+    fn countdown_rounds_up() {
+        assert_eq!(format_countdown(Duration::from_secs(0)), "00:00:00");
+        assert_eq!(format_countdown(Duration::from_millis(1)), "00:00:01");
+        assert_eq!(
+            format_countdown(Duration::from_secs(3 * 3600 + 61)),
+            "03:01:01"
+        );
+    }
 }
@@ -0,0 +1,40 @@
+use std::process::{Command, Stdio};
+
+use crate::Day;
+
+pub fn handle(day: Day, release: bool, part: Option<u8>, runs: Option<u64>, seed: Option<u64>) {
+    // debug builds are the default here, they also catch integer overflows.
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--bin".to_string(),
+        day.to_string(),
+    ];
+
+    if release {
+        cmd_args.push("--release".to_string());
+    }
+
+    cmd_args.push("--".to_string());
+    cmd_args.push("--fuzz".to_string());
+
+    for (flag, value) in [
+        ("--part", part.map(u64::from)),
+        ("--runs", runs),
+        ("--seed", seed),
+    ] {
+        if let Some(value) = value {
+            cmd_args.push(flag.to_string());
+            cmd_args.push(value.to_string());
+        }
+    }
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    cmd.wait().unwrap();
+}
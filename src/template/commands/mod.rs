@@ -1,5 +1,8 @@
 pub mod all;
 pub mod download;
+pub mod fuzz;
 pub mod read;
 pub mod scaffold;
+pub mod scale;
 pub mod solve;
+pub mod today;
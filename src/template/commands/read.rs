@@ -1,16 +1,22 @@
-use std::process;
+use std::{fs, process};
 
-use crate::template::aoc_cli;
+use crate::template::{aoc_cli, markdown};
 use crate::Day;
 
 pub fn handle(day: Day) {
+    // render downloaded puzzles natively, this works offline.
+    if let Ok(puzzle) = fs::read_to_string(aoc_cli::get_puzzle_path(day)) {
+        print!("{}", markdown::render(&puzzle, markdown::terminal_width()));
+        return;
+    }
+
     if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+        eprintln!("puzzle for day {day} has not been downloaded and command \"aoc\" is not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
     if let Err(e) = aoc_cli::read(day) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
-    };
+    }
 }
@@ -0,0 +1,35 @@
+use std::process::{Command, Stdio};
+
+use crate::Day;
+
+pub fn handle(day: Day, part: Option<u8>, seed: Option<u64>) {
+    // timings of unoptimized builds say little about complexity, always use release.
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--release".to_string(),
+        "--bin".to_string(),
+        day.to_string(),
+        "--".to_string(),
+        "--scale".to_string(),
+    ];
+
+    if let Some(part) = part {
+        cmd_args.push("--part".to_string());
+        cmd_args.push(part.to_string());
+    }
+
+    if let Some(seed) = seed {
+        cmd_args.push("--seed".to_string());
+        cmd_args.push(seed.to_string());
+    }
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    cmd.wait().unwrap();
+}
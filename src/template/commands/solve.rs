@@ -2,7 +2,22 @@ use std::process::{Command, Stdio};
 
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
+/// Streams the input from a file (`-` for stdin) instead of loading it, see
+/// [`streaming`](crate::template::streaming).
+pub struct Stream {
+    pub path: String,
+    pub part: Option<u8>,
+}
+
+pub fn handle(
+    day: Day,
+    release: bool,
+    time: bool,
+    submit_part: Option<u8>,
+    variants: bool,
+    threads: Option<usize>,
+    stream: Option<Stream>,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if release {
@@ -20,8 +35,28 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
         cmd_args.push("--time".to_string());
     }
 
-    let mut cmd = Command::new("cargo")
-        .args(&cmd_args)
+    if variants {
+        cmd_args.push("--variants".to_string());
+    }
+
+    if let Some(stream) = stream {
+        cmd_args.push("--stream".to_string());
+        cmd_args.push(stream.path);
+        if let Some(part) = stream.part {
+            cmd_args.push("--part".to_string());
+            cmd_args.push(part.to_string());
+        }
+    }
+
+    let mut cmd = Command::new("cargo");
+    cmd.args(&cmd_args);
+
+    if let Some(threads) = threads {
+        // picked up by `parallel::threads` in the solution.
+        cmd.env("AOC_THREADS", threads.to_string());
+    }
+
+    let mut cmd = cmd
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .spawn()
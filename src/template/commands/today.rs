@@ -0,0 +1,40 @@
+use std::path::Path;
+use std::process;
+
+use crate::template::aoc_cli;
+use crate::template::clock::{PuzzleDate, SystemClock};
+use crate::template::commands::{all, download, read, scaffold};
+use crate::Day;
+
+pub fn handle() {
+    let Some(day) = Day::today() else {
+        eprintln!(
+            "Advent of code is not running right now, pass a day to `cargo scaffold` instead."
+        );
+        process::exit(1);
+    };
+
+    let year = PuzzleDate::now(&SystemClock).year;
+    if let Some(aoc_year) = aoc_cli::get_year() {
+        if i32::from(aoc_year) != year {
+            eprintln!(
+                "Warning: AOC_YEAR is set to {aoc_year}, but it is {year} on the puzzle clock."
+            );
+        }
+    }
+
+    println!("🎄 Today is day {day}.");
+
+    let module_path = all::get_path_for_bin(day);
+    if Path::new(&module_path).exists() {
+        println!("Module file \"{module_path}\" already exists, skipping scaffold.");
+    } else {
+        scaffold::handle(day);
+    }
+
+    println!("---");
+    download::handle(day, false);
+
+    println!("---");
+    read::handle(day);
+}
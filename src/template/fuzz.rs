@@ -0,0 +1,410 @@
+/// Module that fuzzes solutions with mutated example inputs.
+/// Solutions should never panic or hang, not even on malformed input. Failing inputs are
+/// minimized and saved to `data/fuzz`, so they can be turned into regression tests.
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{env, fs, thread};
+
+use crate::template::rng::Rng;
+use crate::template::variants::example_inputs;
+use crate::template::{part_arg, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::Day;
+
+const DEFAULT_RUNS: u64 = 10_000;
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(500);
+/// A hanging run can't be stopped, so its thread keeps running until the fuzzer exits.
+/// Fuzzing a part stops at its first hang, and minimizing it gives up after leaking this many threads.
+const MAX_LEAKED_THREADS: usize = 16;
+
+/// Bytes that tend to matter to parsers.
+const INTERESTING_BYTES: &[u8] = b"\n\r \t0123456789-+:;,.#|()=\x00\xff";
+
+/// Options for [`run_fuzz`], read from the command-line arguments passed to the solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzOptions {
+    pub part: Option<u8>,
+    pub runs: u64,
+    pub seed: u64,
+    pub timeout: Duration,
+}
+
+impl Default for FuzzOptions {
+    fn default() -> Self {
+        Self {
+            part: None,
+            runs: DEFAULT_RUNS,
+            seed: 0,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl FuzzOptions {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = env::args().collect();
+        let value = |flag: &str| {
+            args.iter()
+                .position(|a| a == flag)
+                .and_then(|i| args.get(i + 1))
+                .and_then(|v| v.parse::<u64>().ok())
+        };
+        let defaults = Self::default();
+        Self {
+            part: part_arg(&args),
+            runs: value("--runs").unwrap_or(defaults.runs),
+            seed: value("--seed").unwrap_or(defaults.seed),
+            timeout: value("--timeout").map_or(defaults.timeout, Duration::from_millis),
+        }
+    }
+}
+
+/// How a single run of a solution part ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Ok,
+    /// The part panicked, with the panic location (if known) and message.
+    Panic(String),
+    /// The part did not finish within the time limit.
+    Hang,
+}
+
+impl Outcome {
+    /// A short description used to tell distinct failures apart.
+    fn signature(&self) -> String {
+        match self {
+            Self::Ok => "ok".into(),
+            // messages often contain values (e.g. an index), the location is stable.
+            Self::Panic(msg) => msg.split(": ").next().unwrap_or(msg).into(),
+            Self::Hang => "hang".into(),
+        }
+    }
+}
+
+thread_local! {
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Runs `func` on `input` in a separate thread and waits at most `timeout` for it to finish.
+pub fn run_limited<T: 'static>(func: fn(&str) -> T, input: &str, timeout: Duration) -> Outcome {
+    let (tx, rx) = mpsc::channel();
+    let input = input.to_string();
+
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            func(&input);
+        }));
+        let outcome = match result {
+            Ok(()) => Outcome::Ok,
+            Err(payload) => {
+                let msg = payload
+                    .downcast_ref::<&str>()
+                    .map(ToString::to_string)
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                let location = PANIC_LOCATION.with(|l| l.borrow_mut().take());
+                Outcome::Panic(location.map_or_else(|| msg.clone(), |l| format!("{l}: {msg}")))
+            }
+        };
+        let _ = tx.send(outcome);
+    });
+
+    rx.recv_timeout(timeout).unwrap_or(Outcome::Hang)
+}
+
+/// Applies between one and four random byte-level mutations to `input`.
+pub fn mutate(input: &[u8], corpus: &[Vec<u8>], rng: &mut Rng) -> Vec<u8> {
+    let mut data = input.to_vec();
+
+    for _ in 0..rng.range(1..5) {
+        let len = data.len();
+        match rng.below(10) {
+            // flip a bit
+            0 if len > 0 => {
+                let i = rng.index(len);
+                data[i] ^= 1 << rng.below(8);
+            }
+            // replace a byte
+            1 if len > 0 => {
+                let i = rng.index(len);
+                data[i] = *rng.choose(INTERESTING_BYTES).unwrap();
+            }
+            // insert a byte
+            2 => {
+                let i = rng.index(len + 1);
+                data.insert(i, *rng.choose(INTERESTING_BYTES).unwrap());
+            }
+            // delete a byte
+            3 if len > 0 => {
+                data.remove(rng.index(len));
+            }
+            // delete a range
+            4 if len > 1 => {
+                let start = rng.index(len);
+                let end = start + rng.index(len - start) + 1;
+                data.drain(start..end);
+            }
+            // duplicate a range
+            5 if len > 1 => {
+                let start = rng.index(len);
+                let end = start + rng.index((len - start).min(64)) + 1;
+                let chunk = data[start..end].to_vec();
+                let at = rng.index(len + 1);
+                data.splice(at..at, chunk);
+            }
+            // swap two bytes
+            6 if len > 1 => {
+                let (a, b) = (rng.index(len), rng.index(len));
+                data.swap(a, b);
+            }
+            // truncate
+            7 if len > 0 => data.truncate(rng.index(len)),
+            // replace a digit with a huge number
+            8 => {
+                if let Some(i) = (0..len)
+                    .map(|_| rng.index(len))
+                    .find(|&i| data[i].is_ascii_digit())
+                {
+                    data.splice(i..=i, b"99999999999999999999".iter().copied());
+                }
+            }
+            // splice in a chunk of another input
+            _ => {
+                if let Some(other) = rng.choose(corpus).filter(|o| !o.is_empty()) {
+                    let start = rng.index(other.len());
+                    let end = start + rng.index((other.len() - start).min(64)) + 1;
+                    let at = rng.index(len + 1);
+                    data.splice(at..at, other[start..end].iter().copied());
+                }
+            }
+        }
+    }
+
+    data
+}
+
+/// Shrinks `input` while `still_fails` holds, first by removing lines, then chunks, then single bytes.
+pub fn minimize(input: &str, mut still_fails: impl FnMut(&str) -> bool) -> String {
+    let mut current = input.to_string();
+
+    // lines first, it's cheap and keeps the input readable.
+    let mut i = 0;
+    loop {
+        let lines: Vec<&str> = current.split_inclusive('\n').collect();
+        if i >= lines.len() {
+            break;
+        }
+        let candidate: String = lines
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, l)| *l)
+            .collect();
+        if still_fails(&candidate) {
+            current = candidate;
+        } else {
+            i += 1;
+        }
+    }
+
+    let mut chunk = current.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < current.len() {
+            let end = (start + chunk).min(current.len());
+            if !current.is_char_boundary(start) || !current.is_char_boundary(end) {
+                start += 1;
+                continue;
+            }
+            let candidate = format!("{}{}", &current[..start], &current[end..]);
+            if still_fails(&candidate) {
+                current = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+
+    current
+}
+
+/// Returns a saved failing input, e.g. `read_case("04-1-8f3a9c0d2b1e4f67")` for a regression test.
+pub fn read_case(name: &str) -> String {
+    let cwd = env::current_dir().unwrap();
+    let filepath = cwd.join("data").join("fuzz").join(format!("{name}.txt"));
+    fs::read_to_string(filepath).expect("could not open fuzz case")
+}
+
+fn save_case(day: Day, part: u8, input: &str) -> std::io::Result<String> {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    let name = format!("{day}-{part}-{:016x}", hasher.finish());
+    let dir = env::current_dir()?.join("data").join("fuzz");
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(format!("{name}.txt")), input)?;
+    Ok(name)
+}
+
+/// Fuzzes one part and returns the names of the saved failing inputs. Stops after the first hang.
+pub fn fuzz_part<T: 'static>(
+    day: Day,
+    part: u8,
+    func: fn(&str) -> T,
+    corpus: &[Vec<u8>],
+    options: FuzzOptions,
+) -> Vec<String> {
+    let mut rng = Rng::new(options.seed ^ u64::from(part));
+    let mut seen = HashSet::new();
+    let mut saved = vec![];
+
+    for run in 0..options.runs {
+        let Some(base) = rng.choose(corpus) else {
+            break;
+        };
+        let mutated = mutate(base, corpus, &mut rng);
+        let input = String::from_utf8_lossy(&mutated).into_owned();
+
+        let outcome = run_limited(func, &input, options.timeout);
+        if outcome == Outcome::Ok || !seen.insert(outcome.signature()) {
+            continue;
+        }
+
+        println!("\r  run {run}: {ANSI_BOLD}{outcome:?}{ANSI_RESET}, minimizing...");
+        let signature = outcome.signature();
+        let mut leaked = 0;
+        let minimized = minimize(&input, |candidate| {
+            if leaked >= MAX_LEAKED_THREADS {
+                return false;
+            }
+            let result = run_limited(func, candidate, options.timeout);
+            leaked += usize::from(result == Outcome::Hang);
+            result != Outcome::Ok && result.signature() == signature
+        });
+
+        match save_case(day, part, &minimized) {
+            Ok(name) => {
+                println!(
+                    "  saved {} byte input to {ANSI_ITALIC}data/fuzz/{name}.txt{ANSI_RESET}",
+                    minimized.len()
+                );
+                saved.push(name);
+            }
+            Err(e) => eprintln!("  failed to save fuzz case: {e}"),
+        }
+
+        if outcome == Outcome::Hang {
+            // every further hang would leave another thread spinning.
+            println!("  stopping after run {run}, the part hangs");
+            break;
+        }
+    }
+
+    saved
+}
+
+/// Fuzzes the selected parts of a day, starting from its example files.
+pub fn run_fuzz<A: 'static, B: 'static>(
+    day: Day,
+    part_one: fn(&str) -> Option<A>,
+    part_two: fn(&str) -> Option<B>,
+) {
+    let options = FuzzOptions::from_args();
+    let corpus: Vec<Vec<u8>> = example_inputs(day)
+        .into_iter()
+        .map(|(_, input)| input.into_bytes())
+        .collect();
+
+    if corpus.is_empty() {
+        eprintln!("Day {day} has no example files to start fuzzing from.");
+        std::process::exit(1);
+    }
+
+    // keep panics quiet, they are reported once per distinct failure.
+    panic::set_hook(Box::new(|info| {
+        let location = info.location().map(ToString::to_string);
+        PANIC_LOCATION.with(|l| *l.borrow_mut() = location);
+    }));
+
+    let mut saved = vec![];
+    for part in [1, 2] {
+        if options.part.is_some_and(|p| p != part) {
+            continue;
+        }
+        println!(
+            "{ANSI_BOLD}Part {part}{ANSI_RESET}: {} runs, {:?} time limit",
+            options.runs, options.timeout
+        );
+        let names = if part == 1 {
+            fuzz_part(day, part, part_one, &corpus, options)
+        } else {
+            fuzz_part(day, part, part_two, &corpus, options)
+        };
+        saved.extend(names.into_iter().map(|name| (part, name)));
+    }
+
+    if saved.is_empty() {
+        println!("🎄 No panics or hangs found.");
+    } else {
+        println!("---");
+        println!("Turn failing inputs into regression tests, e.g.:");
+        let (part, name) = &saved[0];
+        let func = if *part == 1 { "part_one" } else { "part_two" };
+        println!("  {func}(&advent_of_code::template::fuzz::read_case(\"{name}\"));");
+    }
+
+    // hanging runs may still be spinning, don't wait for them.
+    std::process::exit(i32::from(!saved.is_empty()));
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn panics_on_xy(input: &str) -> u32 {
+        assert!(!input.contains("xy"), "found xy");
+        0
+    }
+
+    fn hangs_on_h(input: &str) -> u32 {
+        while input.contains('h') {
+            thread::sleep(Duration::from_millis(10));
+        }
+        0
+    }
+
+    #[test]
+    fn detects_panics_and_hangs() {
+        let timeout = Duration::from_millis(100);
+        assert_eq!(run_limited(panics_on_xy, "abc", timeout), Outcome::Ok);
+        assert!(matches!(
+            run_limited(panics_on_xy, "axyc", timeout),
+            Outcome::Panic(msg) if msg.ends_with("found xy")
+        ));
+        assert_eq!(run_limited(hangs_on_h, "abc", timeout), Outcome::Ok);
+        assert_eq!(run_limited(hangs_on_h, "ahc", timeout), Outcome::Hang);
+    }
+
+    #[test]
+    fn minimizes_to_failing_core() {
+        let input = "first line\nabc xy def\nlast line\n";
+        let minimized = minimize(input, |s| s.contains("xy"));
+        assert_eq!(minimized, "xy");
+    }
+
+    #[test]
+    fn mutations_are_deterministic() {
+        let corpus = vec![b"1 2 3\n4 5 6\n".to_vec()];
+        let mutated = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..50)
+                .map(|_| mutate(&corpus[0], &corpus, &mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(mutated(3), mutated(3));
+        assert!(mutated(3).iter().any(|m| m != &corpus[0]));
+    }
+}
@@ -0,0 +1,532 @@
+/// Module that renders downloaded puzzle descriptions (`data/puzzles/NN.md`) for the terminal.
+///
+/// Only the subset of markdown that aoc-cli produces is supported:
+///  1. headings, paragraphs, lists, block quotes and fenced code blocks.
+///  2. `**strong**` (bold), `*emphasis*` (italic), `` `code` `` (italic) and `[links](url)`.
+///  3. `<em>` highlights and emphasis inside code, which advent of code uses for highlighted values (bold).
+///
+/// Every output line is wrapped to the given width and resets its styling at the end,
+/// so the output can be piped into a pager like `less -R`.
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const DEFAULT_WIDTH: usize = 80;
+const MIN_WIDTH: usize = 20;
+const MAX_WIDTH: usize = 100;
+const BASE_URL: &str = "https://adventofcode.com";
+
+/// Returns the width to wrap rendered puzzles at, based on the `COLUMNS` environment variable.
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .map_or(DEFAULT_WIDTH, |c: usize| c.clamp(MIN_WIDTH, MAX_WIDTH))
+}
+
+/// Renders puzzle markdown into styled terminal output, wrapping text at `width` columns.
+pub fn render(markdown: &str, width: usize) -> String {
+    let mut lines = vec![];
+
+    for block in parse_blocks(markdown) {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        match block {
+            Block::Heading(text) => {
+                let mut spans = parse_inline(&text);
+                for span in &mut spans {
+                    span.style.bold = true;
+                }
+                lines.extend(wrap(&spans, width, "", ""));
+            }
+            Block::Paragraph(text) => lines.extend(wrap(&parse_inline(&text), width, "", "")),
+            Block::Quote(text) => lines.extend(wrap(&parse_inline(&text), width, "  ", "  ")),
+            Block::List(items) => {
+                for item in items {
+                    lines.extend(wrap(&parse_inline(&item), width, "  • ", "    "));
+                }
+            }
+            Block::Code(code) => {
+                for line in code {
+                    let spans = parse_html_only(&line);
+                    lines.push(format!("    {}", render_line(&to_chars(&spans))));
+                }
+            }
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, PartialEq, Eq)]
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    Quote(String),
+    List(Vec<String>),
+    Code(Vec<String>),
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    ["* ", "- ", "+ "]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker))
+}
+
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut lines = markdown
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .peekable();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if trimmed.starts_with("```") {
+            let code = lines
+                .by_ref()
+                .take_while(|l| !l.trim_start().starts_with("```"))
+                .map(String::from)
+                .collect();
+            blocks.push(Block::Code(code));
+        } else if trimmed.starts_with('#') {
+            let text = trimmed.trim_start_matches('#').trim();
+            blocks.push(Block::Heading(text.to_string()));
+        } else if let Some(item) = list_item(line) {
+            let mut items = vec![item.to_string()];
+            while let Some(next) = lines.next_if(|l| !l.trim().is_empty()) {
+                if let Some(item) = list_item(next) {
+                    items.push(item.to_string());
+                } else if let Some(last) = items.last_mut() {
+                    last.push(' ');
+                    last.push_str(next.trim());
+                }
+            }
+            blocks.push(Block::List(items));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let mut text = quote.trim().to_string();
+            while let Some(next) = lines.next_if(|l| l.trim_start().starts_with('>')) {
+                text.push(' ');
+                text.push_str(next.trim_start()[1..].trim());
+            }
+            blocks.push(Block::Quote(text));
+        } else {
+            let mut text = trimmed.to_string();
+            while let Some(next) = lines.next_if(|l| {
+                let t = l.trim();
+                !t.is_empty()
+                    && !t.starts_with("```")
+                    && !t.starts_with('#')
+                    && list_item(l).is_none()
+            }) {
+                text.push(' ');
+                text.push_str(next.trim());
+            }
+            blocks.push(Block::Paragraph(text));
+        }
+    }
+
+    blocks
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+}
+
+impl Style {
+    fn escape(self) -> String {
+        let mut s = String::new();
+        if self.bold {
+            s.push_str(ANSI_BOLD);
+        }
+        if self.italic {
+            s.push_str(ANSI_ITALIC);
+        }
+        s
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Span {
+    text: String,
+    style: Style,
+}
+
+#[derive(Default)]
+struct SpanBuilder {
+    spans: Vec<Span>,
+    text: String,
+    style: Style,
+}
+
+impl SpanBuilder {
+    fn flush(&mut self) {
+        if !self.text.is_empty() {
+            self.spans.push(Span {
+                text: std::mem::take(&mut self.text),
+                style: self.style,
+            });
+        }
+    }
+
+    fn set_style(&mut self, style: Style) {
+        if style != self.style {
+            self.flush();
+            self.style = style;
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.text.push_str(s);
+    }
+
+    fn finish(mut self) -> Vec<Span> {
+        self.flush();
+        self.spans
+    }
+}
+
+/// Decodes the html entity at the start of `s`, returning the character and the entity length.
+fn decode_entity(s: &str) -> Option<(char, usize)> {
+    let end = s.find(';').filter(|&e| e <= 8)?;
+    let c = match &s[1..end] {
+        "lt" => '<',
+        "gt" => '>',
+        "amp" => '&',
+        "quot" => '"',
+        "apos" | "#39" => '\'',
+        "nbsp" => ' ',
+        e => char::from_u32(e.strip_prefix('#')?.parse().ok()?)?,
+    };
+    Some((c, end + 1))
+}
+
+/// Handles an html tag at the start of `s`, returning the tag length.
+fn apply_tag(s: &str, bold_depth: &mut u32) -> Option<usize> {
+    let end = s.find('>')?;
+    let tag = s[1..end].to_ascii_lowercase();
+    if tag == "em" || tag.starts_with("em ") {
+        *bold_depth += 1;
+    } else if tag == "/em" {
+        *bold_depth = bold_depth.saturating_sub(1);
+    } else if !tag.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/') {
+        return None;
+    }
+    Some(end + 1)
+}
+
+/// Returns the offset of the `]` closing the link that starts at the beginning of `s`, if it is one.
+fn link_end(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return s[i + 1..].starts_with('(').then_some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_inline(text: &str) -> Vec<Span> {
+    let mut builder = SpanBuilder::default();
+    let mut strong = false;
+    let mut emphasis = None;
+    let mut code = false;
+    let mut bold_depth = 0;
+    let mut links: Vec<usize> = vec![];
+    let mut prev = ' ';
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+        let mut consumed = c.len_utf8();
+
+        match c {
+            '\\' if !code && rest.len() > 1 && rest.as_bytes()[1].is_ascii_punctuation() => {
+                builder.push(char::from(rest.as_bytes()[1]));
+                consumed = 2;
+            }
+            '`' => code = !code,
+            '*' | '_' if rest.starts_with("**") || rest.starts_with("__") => {
+                strong = !strong;
+                consumed = 2;
+            }
+            '*' | '_'
+                if emphasis == Some(c)
+                    || (emphasis.is_none()
+                        && (c == '*' || !prev.is_alphanumeric())
+                        && rest[1..].contains(c)) =>
+            {
+                emphasis = if emphasis.is_some() { None } else { Some(c) };
+            }
+            '<' => match apply_tag(rest, &mut bold_depth) {
+                Some(len) => consumed = len,
+                None => builder.push(c),
+            },
+            '&' => match decode_entity(rest) {
+                Some((decoded, len)) => {
+                    builder.push(decoded);
+                    consumed = len;
+                }
+                None => builder.push(c),
+            },
+            '[' if !code => match link_end(rest) {
+                Some(end) => links.push(i + end),
+                None => builder.push(c),
+            },
+            ']' if !code && links.last() == Some(&i) => {
+                links.pop();
+                if let Some(close) = rest.find(')') {
+                    let url = &rest[2..close];
+                    let url = if url.starts_with('/') {
+                        format!("{BASE_URL}{url}")
+                    } else {
+                        url.to_string()
+                    };
+                    builder.set_style(Style {
+                        bold: false,
+                        italic: true,
+                    });
+                    builder.push_str(&format!(" <{url}>"));
+                    consumed = close + 1;
+                }
+            }
+            _ => builder.push(c),
+        }
+
+        prev = c;
+        i += consumed;
+
+        // `*emphasis*` inside code is how advent of code marks highlighted values.
+        let emphasized = emphasis.is_some();
+        builder.set_style(Style {
+            bold: strong || bold_depth > 0 || (code && emphasized),
+            italic: (emphasized && !code) || (code && !emphasized),
+        });
+    }
+
+    builder.finish()
+}
+
+/// Like [`parse_inline`], but only interprets html tags and entities (for code blocks).
+fn parse_html_only(text: &str) -> Vec<Span> {
+    let mut builder = SpanBuilder::default();
+    let mut bold_depth = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+        let mut consumed = c.len_utf8();
+
+        match c {
+            '<' => match apply_tag(rest, &mut bold_depth) {
+                Some(len) => consumed = len,
+                None => builder.push(c),
+            },
+            '&' => match decode_entity(rest) {
+                Some((decoded, len)) => {
+                    builder.push(decoded);
+                    consumed = len;
+                }
+                None => builder.push(c),
+            },
+            _ => builder.push(c),
+        }
+
+        i += consumed;
+        builder.set_style(Style {
+            bold: bold_depth > 0,
+            italic: false,
+        });
+    }
+
+    builder.finish()
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn to_chars(spans: &[Span]) -> Vec<(char, Style)> {
+    spans
+        .iter()
+        .flat_map(|s| s.text.chars().map(move |c| (c, s.style)))
+        .collect()
+}
+
+fn render_line(chars: &[(char, Style)]) -> String {
+    let mut out = String::new();
+    let mut current = Style::default();
+
+    for &(c, style) in chars {
+        if style != current {
+            if current != Style::default() {
+                out.push_str(ANSI_RESET);
+            }
+            out.push_str(&style.escape());
+            current = style;
+        }
+        out.push(c);
+    }
+
+    if current != Style::default() {
+        out.push_str(ANSI_RESET);
+    }
+
+    out
+}
+
+/// Greedily wraps styled text into lines of at most `width` visible characters.
+/// Words longer than a line are kept whole on a line of their own.
+fn wrap(spans: &[Span], width: usize, first_indent: &str, indent: &str) -> Vec<String> {
+    let chars = to_chars(spans);
+    let words: Vec<&[(char, Style)]> = chars
+        .split(|(c, _)| c.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut lines = vec![];
+    let mut line: Vec<(char, Style)> = vec![];
+    let mut line_width = first_indent.chars().count();
+    let mut prefix = first_indent;
+
+    for word in words {
+        let indent_width = prefix.chars().count();
+        if !line.is_empty() && line_width + 1 + word.len() > width {
+            lines.push(format!("{prefix}{}", render_line(&line)));
+            line.clear();
+            prefix = indent;
+            line_width = indent.chars().count();
+        } else if !line.is_empty() {
+            // the space only keeps the surrounding style if both words share it.
+            let before = line.last().map(|(_, s)| *s).unwrap_or_default();
+            let after = word[0].1;
+            let style = if before == after {
+                before
+            } else {
+                Style::default()
+            };
+            line.push((' ', style));
+            line_width += 1;
+        } else {
+            line_width = indent_width;
+        }
+        line.extend_from_slice(word);
+        line_width += word.len();
+    }
+
+    if !line.is_empty() {
+        lines.push(format!("{prefix}{}", render_line(&line)));
+    }
+
+    lines
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn plain(s: &str) -> String {
+        s.replace(ANSI_BOLD, "")
+            .replace(ANSI_ITALIC, "")
+            .replace(ANSI_RESET, "")
+    }
+
+    #[test]
+    fn renders_headings_bold() {
+        let out = render("## --- Day 1: Trebuchet?! ---\n", 80);
+        assert_eq!(
+            out,
+            format!("{ANSI_BOLD}--- Day 1: Trebuchet?! ---{ANSI_RESET}\n")
+        );
+    }
+
+    #[test]
+    fn renders_emphasis_and_highlights() {
+        let out = render("a *b* **c** <em>d</em> `e` `*f*`", 80);
+        assert_eq!(
+            out,
+            format!(
+                "a {ANSI_ITALIC}b{ANSI_RESET} {ANSI_BOLD}c d{ANSI_RESET} \
+                 {ANSI_ITALIC}e{ANSI_RESET} {ANSI_BOLD}f{ANSI_RESET}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_code_blocks_verbatim() {
+        let out = render(
+            "Example:\n\n```\n1abc2\npqr3stu8vwx <em>*</em>\n```\n\nDone.",
+            10,
+        );
+        assert_eq!(
+            out,
+            format!("Example:\n\n    1abc2\n    pqr3stu8vwx {ANSI_BOLD}*{ANSI_RESET}\n\nDone.\n")
+        );
+    }
+
+    #[test]
+    fn renders_links_and_entities() {
+        let out = render("[Get your input](/2023/day/1/input) &lt;here&gt;", 80);
+        assert_eq!(
+            plain(&out),
+            "Get your input <https://adventofcode.com/2023/day/1/input> <here>\n"
+        );
+        let out = render("[[GitHub]](/auth/github) [a]", 80);
+        assert_eq!(
+            plain(&out),
+            "[GitHub] <https://adventofcode.com/auth/github> [a]\n"
+        );
+    }
+
+    #[test]
+    fn wraps_paragraphs_and_lists() {
+        let out = render(
+            "one two three\nfour five six\n\n* seven eight nine ten\n- eleven",
+            14,
+        );
+        assert_eq!(
+            out,
+            "one two three\nfour five six\n\n  • seven\n    eight nine\n    ten\n  • eleven\n"
+        );
+    }
+
+    #[test]
+    fn restyles_each_wrapped_line() {
+        let out = render("**aaa bbb**", 4);
+        assert_eq!(
+            out,
+            format!("{ANSI_BOLD}aaa{ANSI_RESET}\n{ANSI_BOLD}bbb{ANSI_RESET}\n")
+        );
+    }
+
+    #[test]
+    fn tolerates_crlf_and_escapes() {
+        let out = render("a\\*b\\*\r\nc_d_e\r\n", 80);
+        assert_eq!(out, "a*b* c_d_e\n");
+    }
+}
@@ -2,9 +2,16 @@ use crate::Day;
 use std::{env, fs};
 
 pub mod aoc_cli;
+pub mod clock;
 pub mod commands;
+pub mod fuzz;
+pub mod markdown;
 pub mod readme_benchmarks;
+pub mod rng;
 pub mod runner;
+pub mod scale;
+pub mod streaming;
+pub mod variants;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
@@ -31,18 +38,135 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     f.expect("could not open input file")
 }
 
+/// Helper function that reads the `--part <n>` argument passed to a solution. Exits with an
+/// error if the part is not 1 or 2.
+#[must_use]
+pub fn part_arg(args: &[String]) -> Option<u8> {
+    let flag = args.iter().position(|a| a == "--part")?;
+    match args.get(flag + 1).map(String::as_str) {
+        Some("1") => Some(1),
+        Some("2") => Some(2),
+        value => {
+            eprintln!("--part must be 1 or 2, got {}.", value.unwrap_or("nothing"));
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Creates the constant `DAY` and sets up the input and runner for each part.
+///
+/// Additional implementations of a part can be registered as variants, e.g.
+/// `solution!(1, part_two: [part_two_regex])`. Variants are benchmarked against
+/// each other with `cargo solve <day> --variants`, and a generated test asserts
+/// that they agree with the main implementation on all examples and the real input.
+///
+/// An input generator can be registered with `solution!(11, generator: generate)`,
+/// which enables `cargo scale <day>`. See [`scale::Generator`].
+///
+/// Every solution can be fuzzed with `cargo mutate <day>`, see [`fuzz`].
+///
+/// Parts that read their input from a `BufRead` can be registered with
+/// `solution!(1, stream_one: stream_part_one, stream_two: stream_part_two)`, which enables
+/// `cargo solve <day> --stream <file>`. See [`streaming`].
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
+    (
+        $day:expr
+        $(, part_one: [$($one:ident),* $(,)?])?
+        $(, part_two: [$($two:ident),* $(,)?])?
+        $(, generator: $generator:ident)?
+        $(, stream_one: $stream_one:ident)?
+        $(, stream_two: $stream_two:ident)?
+        $(,)?
+    ) => {
         /// The current day.
         const DAY: advent_of_code::Day = advent_of_code::day!($day);
 
         fn main() {
             use advent_of_code::template::runner::*;
+
+            if std::env::args().any(|x| x == "--scale") {
+                let generator: Option<advent_of_code::template::scale::Generator> =
+                    None $(.or(Some($generator)))?;
+                advent_of_code::template::scale::run_scale(part_one, part_two, generator);
+                return;
+            }
+
+            if std::env::args().any(|x| x == "--fuzz") {
+                advent_of_code::template::fuzz::run_fuzz(DAY, part_one, part_two);
+                return;
+            }
+
+            // streamed inputs can be too big to load, so check before reading the input
+            if let Some(source) = advent_of_code::template::streaming::Source::from_args() {
+                use advent_of_code::template::streaming::{run_stream_part, StreamPart};
+                let stream_one: Option<StreamPart<_>> = None $(.or(Some($stream_one as StreamPart<_>)))?;
+                let stream_two: Option<StreamPart<_>> = None $(.or(Some($stream_two as StreamPart<_>)))?;
+                run_stream_part(part_one, stream_one, &source, 1);
+                run_stream_part(part_two, stream_two, &source, 2);
+                return;
+            }
+
             let input = advent_of_code::template::read_file("inputs", DAY);
+
+            if std::env::args().any(|x| x == "--variants") {
+                use advent_of_code::template::variants::run_variants;
+                $(run_variants(
+                    &[("part_one", part_one as fn(&str) -> _), $((stringify!($one), $one)),*],
+                    &input,
+                    1,
+                );)?
+                $(run_variants(
+                    &[("part_two", part_two as fn(&str) -> _), $((stringify!($two), $two)),*],
+                    &input,
+                    2,
+                );)?
+                return;
+            }
+
             run_part(part_one, &input, DAY, 1);
             run_part(part_two, &input, DAY, 2);
         }
+
+        #[cfg(test)]
+        mod variant_tests {
+            $(#[test]
+            fn part_one_variants_agree() {
+                advent_of_code::template::variants::assert_variants_agree(
+                    super::DAY,
+                    1,
+                    &[("part_one", super::part_one as fn(&str) -> _), $((stringify!($one), super::$one)),*],
+                );
+            })?
+
+            $(#[test]
+            fn part_two_variants_agree() {
+                advent_of_code::template::variants::assert_variants_agree(
+                    super::DAY,
+                    2,
+                    &[("part_two", super::part_two as fn(&str) -> _), $((stringify!($two), super::$two)),*],
+                );
+            })?
+
+            $(#[test]
+            fn stream_one_agrees() {
+                advent_of_code::template::streaming::assert_stream_agrees(
+                    super::DAY,
+                    1,
+                    super::part_one,
+                    super::$stream_one,
+                );
+            })?
+
+            $(#[test]
+            fn stream_two_agrees() {
+                advent_of_code::template::streaming::assert_stream_agrees(
+                    super::DAY,
+                    2,
+                    super::part_two,
+                    super::$stream_two,
+                );
+            })?
+        }
     };
 }
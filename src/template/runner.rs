@@ -17,10 +17,15 @@ pub fn run_part<I: ?Sized, T: Display>(
 ) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples) = run_timed(func, input, |result| {
+        print_result(result.as_ref(), &part_str, "");
+    });
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(
+        result.as_ref(),
+        &part_str,
+        &format_duration(&duration, samples),
+    );
 
     if let Some(result) = result {
         submit_result(result, day, part);
@@ -50,7 +55,11 @@ fn run_timed<I: ?Sized, T>(
     (result, run.0, run.1)
 }
 
-fn bench<I: ?Sized, T>(func: impl Fn(&I) -> T, input: &I, base_time: Duration) -> (Duration, u128) {
+pub(crate) fn bench<I: ?Sized, T>(
+    func: impl Fn(&I) -> T,
+    input: &I,
+    base_time: Duration,
+) -> (Duration, u128) {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
@@ -81,7 +90,7 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
+pub(crate) fn format_duration(duration: &Duration, samples: u128) -> String {
     if samples == 1 {
         format!(" ({duration:.1?})")
     } else {
@@ -89,7 +98,7 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+pub(crate) fn print_result<T: Display>(result: Option<&T>, part: &str, duration_str: &str) {
     let is_final_result = !duration_str.is_empty();
 
     if is_final_result {
@@ -97,23 +106,20 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
     print!("{part}: ");
 
-    match result {
-        Some(result) => {
-            if result.to_string().contains('\n') {
-                print!("▼ {duration_str}");
-                if is_final_result {
-                    println!();
-                    print!("{result}");
-                }
-            } else {
-                print!("{ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
-            }
-        }
-        None => {
-            print!("✖");
+    if let Some(result) = result {
+        if result.to_string().contains('\n') {
+            print!("▼ {duration_str}");
             if is_final_result {
-                print!("             ");
+                println!();
+                print!("{result}");
             }
+        } else {
+            print!("{ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
+        }
+    } else {
+        print!("✖");
+        if is_final_result {
+            print!("             ");
         }
     }
 
@@ -0,0 +1,204 @@
+/// Module that measures how solutions scale on synthetic inputs.
+/// Days opt in by registering an input generator, e.g. `solution!(11, generator: generate)`.
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::template::{part_arg, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+/// Generates a valid puzzle input of the given size from a seed.
+///
+/// What "size" means is up to the day (number of lines, galaxies, ranges, ...),
+/// as long as the work a solution has to do grows with it.
+pub type Generator = fn(usize, u64) -> String;
+
+const DEFAULT_FROM: usize = 16;
+const DEFAULT_TO: usize = 1 << 20;
+/// Stop growing the input once a single run takes longer than this.
+const MAX_RUN_TIME: Duration = Duration::from_millis(250);
+/// Time spent sampling each size.
+const SAMPLE_TIME: Duration = Duration::from_millis(100);
+/// Timings below this are mostly noise and excluded from the fit if possible.
+const MIN_FIT_TIME: Duration = Duration::from_micros(10);
+
+/// Options for [`run_scale`], read from the command-line arguments passed to the solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScaleOptions {
+    pub part: Option<u8>,
+    pub seed: u64,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Default for ScaleOptions {
+    fn default() -> Self {
+        Self {
+            part: None,
+            seed: 0,
+            from: DEFAULT_FROM,
+            to: DEFAULT_TO,
+        }
+    }
+}
+
+impl ScaleOptions {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = env::args().collect();
+        let value = |flag: &str| {
+            args.iter()
+                .position(|a| a == flag)
+                .and_then(|i| args.get(i + 1))
+                .and_then(|v| v.parse().ok())
+        };
+        let defaults = Self::default();
+        Self {
+            part: part_arg(&args),
+            seed: value("--seed").unwrap_or(defaults.seed),
+            from: value("--from").map_or(defaults.from, |v: u64| v as usize),
+            to: value("--to").map_or(defaults.to, |v: u64| v as usize),
+        }
+    }
+}
+
+/// Times each part across doubling input sizes and prints the fitted complexity.
+pub fn run_scale<A, B>(
+    part_one: impl Fn(&str) -> Option<A>,
+    part_two: impl Fn(&str) -> Option<B>,
+    generator: Option<Generator>,
+) {
+    let Some(generator) = generator else {
+        eprintln!("This day has no input generator. Register one with `solution!(<day>, generator: <fn>)`.");
+        std::process::exit(1);
+    };
+
+    let options = ScaleOptions::from_args();
+
+    if options.part.is_none_or(|p| p == 1) {
+        print_scale(1, &measure(&part_one, generator, options));
+    }
+    if options.part.is_none_or(|p| p == 2) {
+        if options.part.is_none() {
+            println!();
+        }
+        print_scale(2, &measure(&part_two, generator, options));
+    }
+}
+
+/// Returns `(size, time per run)` for doubling sizes until a run gets too slow.
+pub fn measure<T>(
+    func: impl Fn(&str) -> Option<T>,
+    generator: Generator,
+    options: ScaleOptions,
+) -> Vec<(usize, Duration)> {
+    let mut timings = vec![];
+    let mut size = options.from.max(1);
+
+    while size <= options.to {
+        let input = generator(size, options.seed);
+        let time = time_per_run(&func, &input);
+        timings.push((size, time));
+        if time > MAX_RUN_TIME {
+            break;
+        }
+        size *= 2;
+    }
+
+    timings
+}
+
+fn time_per_run<T>(func: impl Fn(&str) -> T, input: &str) -> Duration {
+    let mut samples = vec![];
+    let start = Instant::now();
+    while samples.len() < 3 || (start.elapsed() < SAMPLE_TIME && samples.len() < 10_000) {
+        let timer = Instant::now();
+        black_box(func(black_box(input)));
+        samples.push(timer.elapsed());
+    }
+    samples.sort_unstable();
+    samples[samples.len() / 2]
+}
+
+/// Fits `time = c * size^k` with least squares on a log-log scale and returns `k`.
+#[allow(clippy::cast_precision_loss)]
+pub fn fit_exponent(timings: &[(usize, Duration)]) -> Option<f64> {
+    let significant: Vec<_> = timings.iter().filter(|(_, t)| *t >= MIN_FIT_TIME).collect();
+    let points: Vec<(f64, f64)> = if significant.len() >= 3 {
+        significant
+    } else {
+        timings.iter().collect()
+    }
+    .into_iter()
+    .map(|(n, t)| {
+        (
+            (*n as f64).ln(),
+            t.as_secs_f64().max(f64::MIN_POSITIVE).ln(),
+        )
+    })
+    .collect();
+
+    if points.len() < 2 {
+        return None;
+    }
+
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+        (
+            (x - mean_x).mul_add(y - mean_y, cov),
+            (x - mean_x).mul_add(x - mean_x, var),
+        )
+    });
+
+    (var > 0.0).then(|| cov / var)
+}
+
+fn print_scale(part: u8, timings: &[(usize, Duration)]) {
+    println!("{ANSI_BOLD}Part {part}{ANSI_RESET}");
+    for (size, time) in timings {
+        println!("  n = {size:>8}: {time:.1?}");
+    }
+    match fit_exponent(timings) {
+        Some(k) => println!("  {ANSI_BOLD}≈ O(n^{k:.2}){ANSI_RESET}"),
+        None => println!("  {ANSI_ITALIC}not enough data to estimate complexity{ANSI_RESET}"),
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::fit_exponent;
+    use std::time::Duration;
+
+    fn timings(f: impl Fn(f64) -> f64) -> Vec<(usize, Duration)> {
+        (4..12)
+            .map(|i| (1 << i, Duration::from_secs_f64(f(f64::from(1u32 << i)))))
+            .collect()
+    }
+
+    #[test]
+    fn fits_linear() {
+        let k = fit_exponent(&timings(|n| n * 1e-6)).unwrap();
+        assert!((k - 1.0).abs() < 0.01, "{k}");
+    }
+
+    #[test]
+    fn fits_quadratic() {
+        let k = fit_exponent(&timings(|n| n * n * 1e-7)).unwrap();
+        assert!((k - 2.0).abs() < 0.01, "{k}");
+    }
+
+    #[test]
+    fn ignores_noise_floor() {
+        let mut t = timings(|n| n * 1e-6);
+        t.insert(0, (1, Duration::from_nanos(900)));
+        t.insert(1, (2, Duration::from_nanos(950)));
+        let k = fit_exponent(&t).unwrap();
+        assert!((k - 1.0).abs() < 0.01, "{k}");
+    }
+
+    #[test]
+    fn needs_two_points() {
+        assert_eq!(fit_exponent(&[]), None);
+        assert_eq!(fit_exponent(&[(1, Duration::from_secs(1))]), None);
+    }
+}
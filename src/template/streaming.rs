@@ -0,0 +1,147 @@
+/// Module that runs solutions on inputs streamed from a file or stdin, for inputs too big to load.
+/// Streaming parts are registered with the `solution!` macro, e.g. `solution!(1, stream_one: stream_part_one)`,
+/// and read their input with [`LineChunks`](crate::stream::LineChunks) or any other `BufRead` method.
+use std::cell::Cell;
+use std::env;
+use std::fmt::{Debug, Display};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+
+use crate::template::part_arg;
+use crate::template::runner::{format_duration, print_result};
+use crate::template::variants::{example_inputs, real_input};
+use crate::Day;
+
+/// A solution part that reads its input as it goes. Errors reading the input are passed on, so
+/// they aren't mistaken for an input without an answer.
+pub type StreamPart<T> = fn(&mut dyn BufRead) -> io::Result<Option<T>>;
+
+/// Where `--stream` reads the input from.
+#[derive(Debug)]
+pub struct Source {
+    /// A file, or `-` for stdin.
+    path: String,
+    /// Only stream to this part.
+    part: Option<u8>,
+    /// Stdin can only be read once.
+    stdin_used: Cell<bool>,
+}
+
+impl Source {
+    /// Reads `--stream <file>` and `--part <n>` from the command-line arguments passed to the solution.
+    /// Exits with an error if the part is not 1 or 2.
+    pub fn from_args() -> Option<Self> {
+        let args: Vec<String> = env::args().collect();
+        let path = args
+            .iter()
+            .position(|a| a == "--stream")
+            .and_then(|i| args.get(i + 1))?;
+        Some(Self {
+            path: path.clone(),
+            part: part_arg(&args),
+            stdin_used: Cell::new(false),
+        })
+    }
+
+    fn open(&self) -> io::Result<Box<dyn BufRead>> {
+        if self.path != "-" {
+            return Ok(Box::new(BufReader::new(File::open(&self.path)?)));
+        }
+        if self.stdin_used.replace(true) {
+            return Err(io::Error::other(
+                "it was streamed to the other part, pick one with `--part`",
+            ));
+        }
+        Ok(Box::new(io::stdin().lock()))
+    }
+}
+
+/// Streams the input to a part and prints the result, with the time it took to read and solve.
+/// `part_fn` is the part that reads the whole input, which is only used for its result type.
+pub fn run_stream_part<T: Display>(
+    _part_fn: fn(&str) -> Option<T>,
+    stream: Option<StreamPart<T>>,
+    source: &Source,
+    part: u8,
+) {
+    if source.part.is_some_and(|p| p != part) {
+        return;
+    }
+    let part_str = format!("Part {part}");
+    let Some(stream) = stream else {
+        println!("{part_str}: no streaming implementation");
+        return;
+    };
+    let mut reader = match source.open() {
+        Ok(reader) => reader,
+        Err(e) => {
+            eprintln!("{part_str}: could not stream {}: {e}", source.path);
+            return;
+        }
+    };
+
+    let timer = Instant::now();
+    let result = match stream(&mut reader) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{part_str}: could not read {}: {e}", source.path);
+            return;
+        }
+    };
+    print_result(
+        result.as_ref(),
+        &part_str,
+        &format_duration(&timer.elapsed(), 1),
+    );
+}
+
+/// Hands out a few bytes per read, so chunks end all over the input.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.0.len()).min(7);
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Ok(len)
+    }
+}
+
+/// Asserts that a streaming part returns the same result as the part that reads the whole input,
+/// on every example and on the real input, when the input arrives a few bytes at a time.
+pub fn assert_stream_agrees<T: Debug + PartialEq>(
+    day: Day,
+    part: u8,
+    part_fn: fn(&str) -> Option<T>,
+    stream: StreamPart<T>,
+) {
+    let mut inputs = example_inputs(day);
+    if let Some(input) = real_input(day) {
+        inputs.push((format!("inputs/{day}.txt"), input));
+    }
+
+    let mismatches: Vec<String> = inputs
+        .iter()
+        .filter_map(|(file, input)| {
+            let expected = panic::catch_unwind(AssertUnwindSafe(|| part_fn(input))).ok();
+            let mut reader = BufReader::with_capacity(7, Trickle(input.as_bytes()));
+            let streamed = panic::catch_unwind(AssertUnwindSafe(|| {
+                stream(&mut reader).expect("reading from memory can't fail")
+            }))
+            .ok();
+            (streamed != expected).then(|| {
+                format!(
+                    "part {part} on {file}: streaming returned {streamed:?}, expected {expected:?}"
+                )
+            })
+        })
+        .collect();
+
+    assert!(
+        mismatches.is_empty(),
+        "streaming disagrees:\n{}",
+        mismatches.join("\n")
+    );
+}
@@ -0,0 +1,171 @@
+/// Module for solutions with several implementations of a part ("variants").
+/// Variants are registered with the `solution!` macro, e.g. `solution!(1, part_two: [part_two_regex])`.
+use std::fmt::{Debug, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+use std::{env, fs};
+
+use crate::template::runner::bench;
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::Day;
+
+/// A named implementation of a solution part.
+pub type Variant<T> = (&'static str, fn(&str) -> Option<T>);
+
+/// Benchmarks all variants of a part against the same input and prints how they compare.
+pub fn run_variants<T: Display + PartialEq>(variants: &[Variant<T>], input: &str, part: u8) {
+    println!("{ANSI_BOLD}Part {part}{ANSI_RESET}");
+
+    let mut results = vec![];
+    for (name, func) in variants {
+        print!("  {name}");
+        let timer = Instant::now();
+        let result = func(input);
+        let (duration, samples) = bench(func, input, timer.elapsed());
+        // clear the progress output of `bench`.
+        print!("\r{:width$}\r", "", width = name.len() + 16);
+        results.push((*name, result, duration, samples));
+    }
+
+    let Some(fastest) = results.iter().map(|(_, _, d, _)| *d).min() else {
+        return;
+    };
+    let reference = &results[0].1;
+
+    for (name, result, duration, samples) in &results {
+        let ratio = duration.as_secs_f64() / fastest.as_secs_f64().max(f64::MIN_POSITIVE);
+        let result_str = result
+            .as_ref()
+            .map_or_else(|| "✖".to_string(), ToString::to_string);
+        let mismatch = if result == reference {
+            ""
+        } else {
+            " (disagrees!)"
+        };
+        println!(
+            "  {name:<24} {ANSI_BOLD}{result_str}{ANSI_RESET}{mismatch} ({duration:.1?} @ {samples} samples) {ANSI_ITALIC}{ratio:.2}x{ANSI_RESET}"
+        );
+    }
+}
+
+/// Returns every example file for `day` (`NN.txt` and `NN-*.txt`), sorted by name.
+pub fn example_inputs(day: Day) -> Vec<(String, String)> {
+    let dir = env::current_dir().unwrap().join("data").join("examples");
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut inputs: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stem = name.strip_suffix(".txt")?;
+            let is_example = stem == day.to_string()
+                || stem
+                    .strip_prefix(&format!("{day}-"))
+                    .is_some_and(|suffix| !suffix.is_empty());
+            if !is_example {
+                return None;
+            }
+            Some((name, fs::read_to_string(entry.path()).ok()?))
+        })
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+/// Returns the real input for `day`, if it has been downloaded.
+pub fn real_input(day: Day) -> Option<String> {
+    let path = env::current_dir()
+        .ok()?
+        .join("data")
+        .join("inputs")
+        .join(format!("{day}.txt"));
+    fs::read_to_string(path).ok().filter(|s| !s.is_empty())
+}
+
+/// Asserts that all variants of a part return the same result on every example and on the real input.
+/// Variants that panic agree with each other, so parts only need to handle the examples meant for them.
+pub fn assert_variants_agree<T: Debug + PartialEq>(day: Day, part: u8, variants: &[Variant<T>]) {
+    let mut inputs = example_inputs(day);
+    if let Some(input) = real_input(day) {
+        inputs.push((format!("inputs/{day}.txt"), input));
+    }
+
+    let mismatches: Vec<String> = inputs
+        .iter()
+        .flat_map(|(file, input)| {
+            let results: Vec<_> = variants
+                .iter()
+                .map(|(name, func)| {
+                    (
+                        name,
+                        panic::catch_unwind(AssertUnwindSafe(|| func(input))).ok(),
+                    )
+                })
+                .collect();
+            let (reference_name, reference) = &results[0];
+            results[1..]
+                .iter()
+                .filter(|(_, result)| result != reference)
+                .map(|(name, result)| {
+                    format!(
+                        "part {part} on {file}: {name} returned {result:?}, {reference_name} returned {reference:?}"
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    assert!(
+        mismatches.is_empty(),
+        "variants disagree:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+
+    fn char_count(input: &str) -> Option<usize> {
+        Some(input.chars().count())
+    }
+
+    fn byte_count(input: &str) -> Option<usize> {
+        Some(input.len())
+    }
+
+    fn line_count(input: &str) -> Option<usize> {
+        Some(input.lines().count())
+    }
+
+    fn panics(_: &str) -> Option<usize> {
+        panic!("not meant for this input")
+    }
+
+    #[test]
+    fn finds_examples_by_name() {
+        let names: Vec<_> = example_inputs(day!(1))
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["01-2.txt", "01.txt"]);
+        assert!(example_inputs(day!(25)).is_empty());
+    }
+
+    #[test]
+    fn agreeing_variants_pass() {
+        let variants: [Variant<usize>; 2] = [("chars", char_count), ("bytes", byte_count)];
+        assert_variants_agree(day!(1), 1, &variants);
+        assert_variants_agree(day!(1), 1, &[("panics", panics), ("panics too", panics)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "part 2 on 01.txt: lines returned")]
+    fn reports_disagreeing_variants() {
+        let variants: [Variant<usize>; 2] = [("bytes", byte_count), ("lines", line_count)];
+        assert_variants_agree(day!(1), 2, &variants);
+    }
+}
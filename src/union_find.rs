@@ -0,0 +1,212 @@
+/// Module with disjoint sets over the indices `0..n`, with union by size and path compression.
+use std::mem;
+
+/// A partition of `0..len()` into disjoint sets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnionFind {
+    /// The parent of each element; roots are their own parent.
+    parents: Vec<usize>,
+    /// The size of each set, only up to date at its root.
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    /// Creates `len` sets, each holding one element.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            sets: len,
+        }
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Adds a new element in a set of its own, and returns it.
+    pub fn add(&mut self) -> usize {
+        let element = self.parents.len();
+        self.parents.push(element);
+        self.sizes.push(1);
+        self.sets += 1;
+        element
+    }
+
+    /// Returns the representative of the set holding `element`, which is the same for every
+    /// element of a set until it is merged with another.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `element` is not below [`len`](Self::len).
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // point everything on the way straight at the root
+        let mut element = element;
+        while self.parents[element] != root {
+            element = mem::replace(&mut self.parents[element], root);
+        }
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`, returning whether they were separate.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        // hang the smaller set under the larger one, so the trees stay shallow
+        let (large, small) = if self.sizes[a] < self.sizes[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parents[small] = large;
+        self.sizes[large] += self.sizes[small];
+        self.sets -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of elements in the set holding `element`.
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.sets
+    }
+
+    /// Returns the representative of each set.
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(|&element| self.parents[element] == element)
+    }
+
+    /// Returns the elements of each set, in ascending order, with the sets ordered by their
+    /// smallest element.
+    pub fn sets(&mut self) -> Vec<Vec<usize>> {
+        let mut index_of_root = vec![usize::MAX; self.len()];
+        let mut sets: Vec<Vec<usize>> = vec![];
+        for element in 0..self.len() {
+            let root = self.find(element);
+            if index_of_root[root] == usize::MAX {
+                index_of_root[root] = sets.len();
+                sets.push(vec![]);
+            }
+            sets[index_of_root[root]].push(element);
+        }
+        sets
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::collections::Interner;
+    use crate::graph;
+    use crate::grid::Grid;
+
+    #[test]
+    fn merges_sets() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.count(), 3);
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.size(2), 4);
+        assert_eq!(sets.size(5), 1);
+        assert_eq!(sets.sets(), [vec![0, 1, 2, 3], vec![4], vec![5]]);
+        assert_eq!(sets.roots().count(), 3);
+
+        let new = sets.add();
+        assert_eq!((new, sets.len(), sets.count()), (6, 7, 4));
+        sets.union(new, 5);
+        assert_eq!(sets.size(5), 2);
+        assert!(UnionFind::default().is_empty());
+    }
+
+    #[test]
+    fn compresses_long_chains() {
+        let n = 100_000;
+        let mut sets = UnionFind::new(n);
+        for i in 1..n {
+            sets.union(i - 1, i);
+        }
+        assert_eq!(sets.count(), 1);
+        assert_eq!(sets.size(0), n);
+        let root = sets.find(n - 1);
+        assert!((0..n).all(|i| sets.parents[i] == root));
+    }
+
+    #[test]
+    fn interned_names() {
+        let mut names = Interner::new();
+        let mut sets = UnionFind::default();
+        for (a, b) in [
+            ("jqt", "rhn"),
+            ("xhk", "hfx"),
+            ("rhn", "bvb"),
+            ("ntq", "xhk"),
+        ] {
+            for name in [a, b] {
+                if names.intern(name.as_bytes()) == sets.len() {
+                    sets.add();
+                }
+            }
+            sets.union(names.intern(a.as_bytes()), names.intern(b.as_bytes()));
+        }
+        let jqt = names.get(b"jqt").unwrap();
+        assert_eq!(sets.size(jqt), 3);
+        assert_eq!(sets.count(), 2);
+    }
+
+    #[test]
+    fn agrees_with_graph_components() {
+        let grid = Grid::parse("##..#\n#..##\n..#..\n##.##\n#...#").unwrap();
+        let rocks: Vec<_> = grid.find_all(&b'#').collect();
+        let neighbors = |&pos: &(usize, usize)| {
+            grid.neighbors4(pos)
+                .filter(|&next| grid[next] == b'#')
+                .collect::<Vec<_>>()
+        };
+
+        let mut sets = UnionFind::new(grid.cells().len());
+        for &pos in &rocks {
+            for next in neighbors(&pos) {
+                sets.union(grid.index_of(pos).unwrap(), grid.index_of(next).unwrap());
+            }
+        }
+        let mut sizes: Vec<_> = rocks
+            .iter()
+            .map(|&pos| sets.find(grid.index_of(pos).unwrap()))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .map(|root| sets.size(root))
+            .collect();
+        let mut expected: Vec<_> = graph::connected_components(rocks, neighbors)
+            .iter()
+            .map(Vec::len)
+            .collect();
+        sizes.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(sizes, expected);
+    }
+}